@@ -0,0 +1,432 @@
+use std::fmt::Display;
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+use crate::app::{App, Tab};
+
+/// Actions bound to keys outside of popups and text input, which the command
+/// palette lists as well.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Action {
+    Quit,
+    NextTab,
+    PreviousTab,
+    SwitchProfile,
+    PreviousProfile,
+    NextProfile,
+    SwitchBack,
+    ShowError,
+    CommandPalette,
+    TogglePause,
+    FullRefresh,
+    FetchFaster,
+    FetchSlower,
+    Redraw,
+    ToggleMetrics,
+    FocusTasks,
+    ZoomDetails,
+    OpenCollection,
+    XrefExport,
+    XrefPage,
+    CopyReconcileUrl,
+    CopyId,
+    CopyApiUrl,
+    CopyRow,
+    CopyRows,
+    VisualSelect,
+    WidenTable,
+    NarrowTable,
+    ResetSplit,
+    CycleSort,
+    ReverseSort,
+    Filter,
+    CycleCategory,
+    ToggleIdle,
+    ToggleFailures,
+    ClearFilters,
+    ResetView,
+    ToggleTimestamps,
+    ExpandRow,
+    CollapseRow,
+    InspectJson,
+    NextFailure,
+    ToggleFollow,
+    ToggleFreeze,
+    TogglePin,
+    ClearPins,
+    ShowSelectedError,
+    ClearErrors,
+}
+
+/// A key, with the modifiers it needs, bound to an action.
+struct Binding {
+    code: KeyCode,
+    control: bool,
+    action: Action,
+}
+
+const fn key(code: KeyCode, action: Action) -> Binding {
+    Binding {
+        code,
+        control: false,
+        action,
+    }
+}
+
+const fn ctrl(c: char, action: Action) -> Binding {
+    Binding {
+        code: KeyCode::Char(c),
+        control: true,
+        action,
+    }
+}
+
+/// The keymap, the first binding of an action is the one shown in hints.
+const BINDINGS: &[Binding] = &[
+    key(KeyCode::Char('q'), Action::Quit),
+    key(KeyCode::Tab, Action::NextTab),
+    key(KeyCode::BackTab, Action::PreviousTab),
+    key(KeyCode::Char('p'), Action::SwitchProfile),
+    key(KeyCode::Char('['), Action::PreviousProfile),
+    key(KeyCode::Char(']'), Action::NextProfile),
+    key(KeyCode::Char('u'), Action::SwitchBack),
+    key(KeyCode::Char('e'), Action::ShowError),
+    ctrl('p', Action::CommandPalette),
+    key(KeyCode::Char(' '), Action::TogglePause),
+    key(KeyCode::F(5), Action::FullRefresh),
+    key(KeyCode::Char('R'), Action::FullRefresh),
+    key(KeyCode::Char('+'), Action::FetchFaster),
+    key(KeyCode::Char('-'), Action::FetchSlower),
+    ctrl('l', Action::Redraw),
+    key(KeyCode::F(12), Action::ToggleMetrics),
+    key(KeyCode::Enter, Action::FocusTasks),
+    key(KeyCode::Char('z'), Action::ZoomDetails),
+    key(KeyCode::Char('o'), Action::OpenCollection),
+    key(KeyCode::Char('x'), Action::XrefExport),
+    key(KeyCode::Char('X'), Action::XrefPage),
+    key(KeyCode::Char('r'), Action::CopyReconcileUrl),
+    key(KeyCode::Char('y'), Action::CopyId),
+    key(KeyCode::Char('Y'), Action::CopyApiUrl),
+    key(KeyCode::Char('T'), Action::CopyRow),
+    ctrl('y', Action::CopyRows),
+    key(KeyCode::Char('v'), Action::VisualSelect),
+    key(KeyCode::Char('>'), Action::WidenTable),
+    Binding {
+        code: KeyCode::Right,
+        control: true,
+        action: Action::WidenTable,
+    },
+    key(KeyCode::Char('<'), Action::NarrowTable),
+    Binding {
+        code: KeyCode::Left,
+        control: true,
+        action: Action::NarrowTable,
+    },
+    key(KeyCode::Char('='), Action::ResetSplit),
+    key(KeyCode::Char('s'), Action::CycleSort),
+    key(KeyCode::Char('S'), Action::ReverseSort),
+    key(KeyCode::Char('/'), Action::Filter),
+    key(KeyCode::Char('f'), Action::CycleCategory),
+    key(KeyCode::Char('a'), Action::ToggleIdle),
+    key(KeyCode::Char('E'), Action::ToggleFailures),
+    key(KeyCode::Char('C'), Action::ClearFilters),
+    ctrl('r', Action::ResetView),
+    key(KeyCode::Char('t'), Action::ToggleTimestamps),
+    key(KeyCode::Right, Action::ExpandRow),
+    key(KeyCode::Char('l'), Action::ExpandRow),
+    key(KeyCode::Left, Action::CollapseRow),
+    key(KeyCode::Char('h'), Action::CollapseRow),
+    key(KeyCode::Char('D'), Action::InspectJson),
+    key(KeyCode::Char('!'), Action::NextFailure),
+    key(KeyCode::Char('w'), Action::ToggleFollow),
+    key(KeyCode::Char('F'), Action::ToggleFreeze),
+    key(KeyCode::Char('m'), Action::TogglePin),
+    key(KeyCode::Char('M'), Action::ClearPins),
+    key(KeyCode::Enter, Action::ShowSelectedError),
+    key(KeyCode::Char('c'), Action::ClearErrors),
+];
+
+impl Action {
+    pub const ALL: [Action; 48] = [
+        Action::Quit,
+        Action::NextTab,
+        Action::PreviousTab,
+        Action::SwitchProfile,
+        Action::PreviousProfile,
+        Action::NextProfile,
+        Action::SwitchBack,
+        Action::ShowError,
+        Action::CommandPalette,
+        Action::TogglePause,
+        Action::FullRefresh,
+        Action::FetchFaster,
+        Action::FetchSlower,
+        Action::Redraw,
+        Action::ToggleMetrics,
+        Action::FocusTasks,
+        Action::ZoomDetails,
+        Action::OpenCollection,
+        Action::XrefExport,
+        Action::XrefPage,
+        Action::CopyReconcileUrl,
+        Action::CopyId,
+        Action::CopyApiUrl,
+        Action::CopyRow,
+        Action::CopyRows,
+        Action::VisualSelect,
+        Action::WidenTable,
+        Action::NarrowTable,
+        Action::ResetSplit,
+        Action::CycleSort,
+        Action::ReverseSort,
+        Action::Filter,
+        Action::CycleCategory,
+        Action::ToggleIdle,
+        Action::ToggleFailures,
+        Action::ClearFilters,
+        Action::ResetView,
+        Action::ToggleTimestamps,
+        Action::ExpandRow,
+        Action::CollapseRow,
+        Action::InspectJson,
+        Action::NextFailure,
+        Action::ToggleFollow,
+        Action::ToggleFreeze,
+        Action::TogglePin,
+        Action::ClearPins,
+        Action::ShowSelectedError,
+        Action::ClearErrors,
+    ];
+
+    /// The action bound to `key_event` that is available to `app`, if any.
+    /// Tabs can bind the same key to different actions.
+    pub fn from_key(key_event: &KeyEvent, app: &App) -> Option<Action> {
+        let control = key_event.modifiers.contains(KeyModifiers::CONTROL);
+        BINDINGS
+            .iter()
+            .filter(|binding| binding.code == key_event.code && binding.control == control)
+            .map(|binding| binding.action)
+            .find(|action| action.available(app))
+    }
+
+    /// The key shown for the action, like "Ctrl+Y".
+    pub fn key_label(self) -> String {
+        let Some(binding) = BINDINGS.iter().find(|binding| binding.action == self) else {
+            return String::default();
+        };
+        let code = match binding.code {
+            KeyCode::Char(' ') => "Space".to_string(),
+            KeyCode::Char(c) => c.to_string(),
+            KeyCode::BackTab => "Shift+Tab".to_string(),
+            code => code.to_string(),
+        };
+        match binding.control {
+            true => format!("Ctrl+{}", code.to_uppercase()),
+            false => code,
+        }
+    }
+
+    /// Whether to ask before performing the action. Quitting only asks if
+    /// the config says so.
+    pub fn needs_confirmation(self, app: &App) -> bool {
+        match self {
+            Action::Quit => app.config.confirm_quit,
+            action => action.confirmation().is_some(),
+        }
+    }
+
+    /// Whether the action works in every tab, rather than only in the jobs tab.
+    pub fn is_global(self) -> bool {
+        matches!(
+            self,
+            Action::Quit
+                | Action::NextTab
+                | Action::PreviousTab
+                | Action::SwitchProfile
+                | Action::PreviousProfile
+                | Action::NextProfile
+                | Action::SwitchBack
+                | Action::ShowError
+                | Action::CommandPalette
+                | Action::TogglePause
+                | Action::FullRefresh
+                | Action::FetchFaster
+                | Action::FetchSlower
+                | Action::Redraw
+                | Action::ToggleMetrics
+        )
+    }
+
+    /// Whether the action does anything in the tab `app` is in.
+    pub fn available(self, app: &App) -> bool {
+        match self {
+            Action::ShowError => app.last_error.is_some(),
+            Action::ShowSelectedError | Action::ClearErrors => app.tab == Tab::Errors,
+            action if action.is_global() => true,
+            _ => app.tab == Tab::Jobs,
+        }
+    }
+
+    /// The question to confirm before performing the action, for those that
+    /// throw something away.
+    pub fn confirmation(self) -> Option<&'static str> {
+        match self {
+            Action::Quit => Some("Quit aleph-tui?"),
+            Action::ClearPins => Some("Unpin all collections?"),
+            Action::ClearErrors => Some("Clear the error history?"),
+            _ => None,
+        }
+    }
+}
+
+impl Display for Action {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Action::Quit => "Quit",
+            Action::NextTab => "Next tab",
+            Action::PreviousTab => "Previous tab",
+            Action::SwitchProfile => "Switch profile",
+            Action::PreviousProfile => "Previous profile",
+            Action::NextProfile => "Next profile",
+            Action::SwitchBack => "Back to the previous profile",
+            Action::ShowError => "Show error details",
+            Action::CommandPalette => "Command palette",
+            Action::TogglePause => "Pause/resume fetching",
+            Action::FullRefresh => "Refetch everything",
+            Action::FetchFaster => "Fetch more often",
+            Action::FetchSlower => "Fetch less often",
+            Action::Redraw => "Clear the error and redraw",
+            Action::FocusTasks => "Focus the task table",
+            Action::ZoomDetails => "Zoom the details pane",
+            Action::OpenCollection => "Open collection in the browser",
+            Action::XrefExport => "Download cross-referencing results",
+            Action::XrefPage => "Open cross-referencing page",
+            Action::CopyReconcileUrl => "Copy reconciliation URL",
+            Action::CopyId => "Copy collection id",
+            Action::CopyApiUrl => "Copy collection API URL",
+            Action::CopyRow => "Copy selected row",
+            Action::CopyRows => "Copy all rows",
+            Action::VisualSelect => "Select a range of rows",
+            Action::WidenTable => "Widen the table",
+            Action::NarrowTable => "Narrow the table",
+            Action::ResetSplit => "Reset the split",
+            Action::CycleSort => "Sort by next column",
+            Action::ReverseSort => "Reverse sort order",
+            Action::Filter => "Filter collections",
+            Action::CycleCategory => "Filter by category",
+            Action::ToggleIdle => "Toggle active only",
+            Action::ToggleFailures => "Toggle failures only",
+            Action::ClearFilters => "Clear all filters",
+            Action::ResetView => "Reset the view",
+            Action::ToggleTimestamps => "Toggle relative timestamps",
+            Action::ExpandRow => "Expand stages",
+            Action::CollapseRow => "Collapse stages",
+            Action::InspectJson => "Inspect the raw JSON",
+            Action::NextFailure => "Jump to the next failure",
+            Action::ToggleFollow => "Follow the most active collection",
+            Action::ToggleFreeze => "Freeze/thaw the table",
+            Action::TogglePin => "Pin/unpin collection",
+            Action::ClearPins => "Unpin all collections",
+            Action::ShowSelectedError => "Show the selected error",
+            Action::ClearErrors => "Clear the error history",
+            Action::ToggleMetrics => "Toggle the debug overlay",
+        };
+        f.write_str(name)
+    }
+}
+
+/// How well `query` matches `text` when its characters appear in order,
+/// ignoring case: the length of the shortest stretch of `text` containing
+/// them, lower is better. `None` if they don't all appear.
+pub fn fuzzy_score(text: &str, query: &str) -> Option<usize> {
+    let text: Vec<char> = text.to_lowercase().chars().collect();
+    let query: Vec<char> = query.to_lowercase().chars().collect();
+    let Some(first) = query.first() else {
+        return Some(0);
+    };
+    (0..text.len())
+        .filter(|start| text[*start] == *first)
+        .filter_map(|start| {
+            let mut position = start;
+            for c in &query[1..] {
+                position += 1 + text[position + 1..].iter().position(|t| t == c)?;
+            }
+            Some(position - start + 1)
+        })
+        .min()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_key() {
+        let mut app = App::with_config(
+            toml::from_str(
+                r#"
+                default = "one"
+                [profiles.one]
+                url = "url1"
+                token = "token1"
+                "#,
+            )
+            .unwrap(),
+        );
+        let key =
+            |code, modifiers, app: &App| Action::from_key(&KeyEvent::new(code, modifiers), app);
+        assert_eq!(
+            key(KeyCode::Char('p'), KeyModifiers::NONE, &app),
+            Some(Action::SwitchProfile)
+        );
+        assert_eq!(
+            key(KeyCode::Char('p'), KeyModifiers::CONTROL, &app),
+            Some(Action::CommandPalette)
+        );
+        assert_eq!(
+            key(KeyCode::Char('S'), KeyModifiers::SHIFT, &app),
+            Some(Action::ReverseSort)
+        );
+        assert_eq!(
+            key(KeyCode::Right, KeyModifiers::CONTROL, &app),
+            Some(Action::WidenTable)
+        );
+        assert_eq!(key(KeyCode::Char('b'), KeyModifiers::NONE, &app), None);
+
+        assert_eq!(
+            key(KeyCode::Char('T'), KeyModifiers::SHIFT, &app),
+            Some(Action::CopyRow)
+        );
+        assert_eq!(key(KeyCode::Char('c'), KeyModifiers::NONE, &app), None);
+        app.tab = Tab::System;
+        assert_eq!(key(KeyCode::Char('T'), KeyModifiers::SHIFT, &app), None);
+        assert_eq!(key(KeyCode::Char('c'), KeyModifiers::NONE, &app), None);
+        app.tab = Tab::Errors;
+        assert_eq!(
+            key(KeyCode::Char('c'), KeyModifiers::NONE, &app),
+            Some(Action::ClearErrors)
+        );
+    }
+
+    #[test]
+    fn test_key_label() {
+        assert_eq!(Action::CycleSort.key_label(), "s");
+        assert_eq!(Action::CopyRows.key_label(), "Ctrl+Y");
+        assert_eq!(Action::PreviousTab.key_label(), "Shift+Tab");
+        assert_eq!(Action::FocusTasks.key_label(), "Enter");
+        assert_eq!(Action::ToggleMetrics.key_label(), "F12");
+        assert_eq!(Action::TogglePause.key_label(), "Space");
+        for action in Action::ALL {
+            assert!(!action.key_label().is_empty(), "{} has no key", action);
+        }
+    }
+
+    #[test]
+    fn test_fuzzy_score() {
+        assert_eq!(fuzzy_score("Switch profile", ""), Some(0));
+        assert_eq!(fuzzy_score("Switch profile", "prof"), Some(4));
+        assert_eq!(fuzzy_score("Switch profile", "SWP"), Some(8));
+        assert_eq!(fuzzy_score("Switch profile", "pw"), None);
+        assert_eq!(fuzzy_score("Copy all rows", "cr"), Some(10));
+    }
+}
@@ -0,0 +1,198 @@
+use std::{
+    fmt::Debug,
+    sync::OnceLock,
+    time::{Duration, Instant},
+};
+
+use color_eyre::Result;
+use futures_util::future::BoxFuture;
+use reqwest::header::{AUTHORIZATION, USER_AGENT};
+
+use crate::{
+    app::Profile,
+    models::{Metadata, Role, Status},
+};
+
+/// User agent sent with every request.
+const USER_AGENT_VALUE: &str = concat!("aleph-tui/", env!("CARGO_PKG_VERSION"));
+
+/// A status as fetched, with what the metrics tab shows about the request.
+#[derive(Debug)]
+pub struct StatusResponse {
+    pub status: Status,
+    /// Request ID the server gave the response, if any.
+    pub request_id: Option<String>,
+    /// Size of the response body.
+    pub bytes: usize,
+    /// Time taken to parse the body.
+    pub parse_duration: Duration,
+}
+
+/// What fetching the status, then the metadata and role of a profile
+/// brought back.
+#[derive(Debug)]
+pub struct Fetched {
+    /// Index of the profile fetched from.
+    pub profile: usize,
+    pub status: Result<StatusResponse>,
+    /// Only fetched when the status came in.
+    pub metadata: Option<Result<Metadata>>,
+    pub role: Option<Role>,
+    /// Time taken by all of the requests.
+    pub duration: Duration,
+}
+
+impl Fetched {
+    /// Fetches from `client`, which talks to the profile at `profile`.
+    pub async fn fetch(client: &dyn AlephClient, profile: usize) -> Self {
+        let started = Instant::now();
+        let status = client.status().await;
+        let (metadata, role) = match status {
+            Ok(_) => (Some(client.metadata().await), client.role().await),
+            Err(_) => (None, None),
+        };
+        Self {
+            profile,
+            status,
+            metadata,
+            role,
+            duration: started.elapsed(),
+        }
+    }
+}
+
+/// The requests made to an Aleph instance.
+pub trait AlephClient: Debug + Send + Sync {
+    /// Jobs running on the instance.
+    fn status(&self) -> BoxFuture<'_, Result<StatusResponse>>;
+
+    /// About the instance.
+    fn metadata(&self) -> BoxFuture<'_, Result<Metadata>>;
+
+    /// Who the token belongs to. This is informational only, so failures
+    /// come back as `None`.
+    fn role(&self) -> BoxFuture<'_, Option<Role>>;
+}
+
+/// Talks to the instance of a profile over HTTP.
+#[derive(Debug)]
+pub struct HttpClient {
+    /// Set up on the first request, since that takes a while.
+    client: OnceLock<reqwest::Client>,
+    profile: Profile,
+}
+
+impl HttpClient {
+    pub fn new(profile: &Profile) -> Self {
+        Self {
+            client: OnceLock::new(),
+            profile: profile.clone(),
+        }
+    }
+
+    fn get(&self, path: &str) -> reqwest::RequestBuilder {
+        self.client
+            .get_or_init(reqwest::Client::new)
+            .get(self.profile.api_url(path))
+            .header(AUTHORIZATION, self.profile.authorization())
+            .header(USER_AGENT, USER_AGENT_VALUE)
+    }
+}
+
+impl AlephClient for HttpClient {
+    fn status(&self) -> BoxFuture<'_, Result<StatusResponse>> {
+        Box::pin(async {
+            let response = self.get("status").send().await?.error_for_status()?;
+            let request_id = response
+                .headers()
+                .get("x-request-id")
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_string);
+            let body = response.bytes().await?;
+            let started = Instant::now();
+            let status = Status::from_json(&body)?;
+            Ok(StatusResponse {
+                status,
+                request_id,
+                bytes: body.len(),
+                parse_duration: started.elapsed(),
+            })
+        })
+    }
+
+    fn metadata(&self) -> BoxFuture<'_, Result<Metadata>> {
+        Box::pin(async {
+            Ok(self
+                .get("metadata")
+                .send()
+                .await?
+                .error_for_status()?
+                .json()
+                .await?)
+        })
+    }
+
+    fn role(&self) -> BoxFuture<'_, Option<Role>> {
+        Box::pin(async {
+            if self.profile.is_anonymous() {
+                return None;
+            }
+            self.get("roles/me")
+                .send()
+                .await
+                .ok()?
+                .error_for_status()
+                .ok()?
+                .json()
+                .await
+                .ok()
+        })
+    }
+}
+
+/// Answers with canned responses, failing where there are none, and counts
+/// the requests made.
+#[cfg(test)]
+#[derive(Debug, Default)]
+pub struct MockClient {
+    pub status: Option<Status>,
+    pub metadata: Option<Metadata>,
+    pub role: Option<Role>,
+    pub requests: std::sync::Arc<std::sync::atomic::AtomicUsize>,
+}
+
+#[cfg(test)]
+impl MockClient {
+    fn request(&self) {
+        self.requests
+            .fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+    }
+}
+
+#[cfg(test)]
+impl AlephClient for MockClient {
+    fn status(&self) -> BoxFuture<'_, Result<StatusResponse>> {
+        self.request();
+        let status = self.status.clone();
+        Box::pin(async {
+            Ok(StatusResponse {
+                status: status.ok_or_else(|| color_eyre::eyre::eyre!("no status"))?,
+                request_id: Some("mock".to_string()),
+                bytes: 0,
+                parse_duration: Duration::ZERO,
+            })
+        })
+    }
+
+    fn metadata(&self) -> BoxFuture<'_, Result<Metadata>> {
+        self.request();
+        let metadata = self.metadata.clone();
+        Box::pin(async { metadata.ok_or_else(|| color_eyre::eyre::eyre!("no metadata")) })
+    }
+
+    fn role(&self) -> BoxFuture<'_, Option<Role>> {
+        self.request();
+        let role = self.role.clone();
+        Box::pin(async { role })
+    }
+}
@@ -0,0 +1,52 @@
+use std::{io::Write, sync::Mutex};
+
+use arboard::Clipboard;
+use base64::{engine::general_purpose::STANDARD, Engine};
+use color_eyre::{eyre::eyre, Result};
+
+/// Kept open for the whole session, since on Linux the copied text is served
+/// by the process that set it and goes away with the `Clipboard`.
+static CLIPBOARD: Mutex<Option<Clipboard>> = Mutex::new(None);
+
+/// Puts `text` on the system clipboard. Over SSH, where the system clipboard
+/// is out of reach, the terminal is asked to do it with an OSC 52 sequence.
+pub fn copy(text: &str) -> Result<()> {
+    if is_ssh() {
+        return copy_osc52(text);
+    }
+    let mut clipboard = CLIPBOARD
+        .lock()
+        .map_err(|_| eyre!("clipboard is unavailable"))?;
+    let clipboard = match clipboard.as_mut() {
+        Some(clipboard) => clipboard,
+        None => clipboard.insert(Clipboard::new()?),
+    };
+    clipboard.set_text(text)?;
+    Ok(())
+}
+
+fn is_ssh() -> bool {
+    std::env::var_os("SSH_TTY").is_some() || std::env::var_os("SSH_CONNECTION").is_some()
+}
+
+/// Writes to stderr, where the terminal UI is drawn.
+fn copy_osc52(text: &str) -> Result<()> {
+    let mut stderr = std::io::stderr();
+    write!(stderr, "{}", osc52(text))?;
+    stderr.flush()?;
+    Ok(())
+}
+
+fn osc52(text: &str) -> String {
+    format!("\x1b]52;c;{}\x07", STANDARD.encode(text))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_osc52() {
+        assert_eq!(osc52("94\tlabel"), "\x1b]52;c;OTQJbGFiZWw=\x07");
+    }
+}
@@ -1,87 +1,448 @@
-use std::{
-    sync::mpsc,
-    thread,
-    time::{Duration, Instant},
+use std::{fmt, future::Future, io, time::Duration};
+
+use tokio::{
+    sync::{mpsc, watch},
+    time::{self, Instant},
 };
 
 use color_eyre::Result;
-use crossterm::event::{self, Event as CrosstermEvent, KeyEvent, MouseEvent};
+use crossterm::event::{
+    Event as CrosstermEvent, EventStream, KeyCode, KeyEvent, KeyEventKind, MouseEvent,
+};
+use futures_util::{stream::BoxStream, StreamExt};
+
+use crate::{client::Fetched, tui};
 
 /// Terminal events.
-#[derive(Clone, Copy, Debug)]
+#[derive(Debug)]
 pub enum Event {
-    /// Terminal tick.
+    /// Time to draw the next frame.
     Tick,
+    /// The next fetch is due.
+    Fetch,
+    /// A fetch started with [`EventHandler::spawn_fetch`] came back.
+    Fetched(Box<Fetched>),
     /// Key press.
     Key(KeyEvent),
     /// Mouse click/scroll.
     Mouse(MouseEvent),
     /// Terminal resize.
     Resize(u16, u16),
+    /// The process was asked to terminate, e.g. with SIGTERM.
+    Shutdown,
 }
 
-/// Terminal event handler.
+/// Why no event could be read.
 #[derive(Debug)]
+pub enum EventError {
+    /// The terminal went away, e.g. with the SSH connection.
+    Disconnected,
+    /// Reading from the terminal failed.
+    Io(io::Error),
+}
+
+impl fmt::Display for EventError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EventError::Disconnected => write!(f, "the terminal went away"),
+            EventError::Io(e) => write!(f, "unable to read terminal events: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for EventError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            EventError::Disconnected => None,
+            EventError::Io(e) => Some(e),
+        }
+    }
+}
+
+/// How long the frame rate stays up after input.
+const BUSY_DURATION: Duration = Duration::from_secs(1);
+
+/// How long to wait for a clean exit after a shutdown signal.
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Terminal event handler.
+///
+/// Terminal events come in as they happen, ticks at the frame rate, doubled
+/// for a moment after input. Dropping it stops reading from the terminal.
 pub struct EventHandler {
-    /// Event sender channel.
-    #[allow(dead_code)]
-    sender: mpsc::Sender<Event>,
-    /// Event receiver channel.
-    receiver: mpsc::Receiver<Event>,
-    /// Event handler thread.
-    #[allow(dead_code)]
-    handler: thread::JoinHandle<()>,
+    /// Terminal events.
+    stream: BoxStream<'static, io::Result<CrosstermEvent>>,
+    /// Time between ticks while idle.
+    frame: Duration,
+    /// When the next tick is due.
+    next_tick: Instant,
+    /// Until when ticks come at twice the rate.
+    busy_until: Instant,
+    /// Set once a signal asked us to shut down.
+    shutdown: watch::Receiver<bool>,
+    /// Results of the fetches running on their own tasks.
+    fetched_tx: mpsc::UnboundedSender<Fetched>,
+    fetched_rx: mpsc::UnboundedReceiver<Fetched>,
 }
 
 impl EventHandler {
     /// Constructs a new instance of [`EventHandler`].
-    pub fn new(tick_rate: u64) -> Self {
-        let tick_rate = Duration::from_millis(tick_rate);
-        let (sender, receiver) = mpsc::channel();
-        let handler = {
-            let sender = sender.clone();
-            thread::spawn(move || {
-                let mut last_tick = Instant::now();
-                loop {
-                    let timeout = tick_rate
-                        .checked_sub(last_tick.elapsed())
-                        .unwrap_or(tick_rate);
-
-                    if event::poll(timeout).expect("unable to poll for event") {
-                        match event::read().expect("unable to read event") {
-                            CrosstermEvent::Key(e) => {
-                                if e.kind == event::KeyEventKind::Press {
-                                    sender.send(Event::Key(e))
-                                } else {
-                                    Ok(()) // ignore KeyEventKind::Release on windows
-                                }
-                            }
-                            CrosstermEvent::Mouse(e) => sender.send(Event::Mouse(e)),
-                            CrosstermEvent::Resize(w, h) => sender.send(Event::Resize(w, h)),
-                            _ => unimplemented!(),
-                        }
-                        .expect("failed to send terminal event")
+    pub fn new(frame_rate: u32) -> Result<Self> {
+        Self::with_stream(EventStream::new().boxed(), frame_rate)
+    }
+
+    /// Like [`EventHandler::new`], with terminal events from `stream`.
+    fn with_stream(
+        stream: BoxStream<'static, io::Result<CrosstermEvent>>,
+        frame_rate: u32,
+    ) -> Result<Self> {
+        let now = Instant::now();
+        let (fetched_tx, fetched_rx) = mpsc::unbounded_channel();
+        Ok(Self {
+            stream,
+            frame: Duration::from_secs(1) / frame_rate.max(1),
+            next_tick: now,
+            busy_until: now,
+            shutdown: watch_signals(Signals::new()?),
+            fetched_tx,
+            fetched_rx,
+        })
+    }
+
+    /// Whether a signal asked us to shut down.
+    pub fn shutdown_requested(&self) -> bool {
+        *self.shutdown.borrow()
+    }
+
+    /// Runs `fetch` on a task of its own, its result comes in as
+    /// [`Event::Fetched`].
+    pub fn spawn_fetch(&self, fetch: impl Future<Output = Fetched> + Send + 'static) {
+        let fetched_tx = self.fetched_tx.clone();
+        tokio::spawn(async move {
+            // Only fails once the handler is gone, and nobody waits anymore.
+            let _ = fetched_tx.send(fetch.await);
+        });
+    }
+
+    /// Waits for the next terminal event, tick, due fetch, fetch result or
+    /// shutdown signal, whichever comes first. `fetch_in` is `None` while no
+    /// fetch is due.
+    pub async fn next(&mut self, fetch_in: Option<Duration>) -> Result<Event, EventError> {
+        let fetch_at = fetch_in.map(|fetch_in| Instant::now() + fetch_in);
+        loop {
+            tokio::select! {
+                event = self.stream.next() => {
+                    if let Some(event) = convert(event)? {
+                        self.busy_until = Instant::now() + BUSY_DURATION;
+                        return Ok(event);
                     }
+                }
+                _ = time::sleep_until(self.next_tick) => {
+                    self.next_tick = Instant::now() + self.tick_period();
+                    return Ok(Event::Tick);
+                }
+                _ = time::sleep_until(fetch_at.unwrap_or(self.next_tick)), if fetch_at.is_some() => {
+                    return Ok(Event::Fetch);
+                }
+                Some(fetched) = self.fetched_rx.recv() => {
+                    return Ok(Event::Fetched(Box::new(fetched)));
+                }
+                Ok(()) = self.shutdown.changed() => return Ok(Event::Shutdown),
+            }
+        }
+    }
 
-                    if last_tick.elapsed() >= tick_rate {
-                        sender.send(Event::Tick).expect("failed to send tick event");
-                        last_tick = Instant::now();
+    fn tick_period(&self) -> Duration {
+        match Instant::now() < self.busy_until {
+            true => self.frame / 2,
+            false => self.frame,
+        }
+    }
+
+    /// Receive the next terminal event if one is waiting, without waiting
+    /// for one. Polling the stream from the task that waits in
+    /// [`EventHandler::next`] keeps it waking that task.
+    pub async fn try_next(&mut self) -> Result<Option<Event>, EventError> {
+        loop {
+            tokio::select! {
+                biased;
+                event = self.stream.next() => {
+                    if let Some(event) = convert(event)? {
+                        self.busy_until = Instant::now() + BUSY_DURATION;
+                        return Ok(Some(event));
                     }
                 }
+                _ = std::future::ready(()) => return Ok(None),
+            }
+        }
+    }
+}
+
+/// Waits for `signals` on a task of its own, so that they are seen even
+/// while the main loop is stuck. The first one sets the returned flag and
+/// exits the process after [`SHUTDOWN_TIMEOUT`] if it is still running then,
+/// since the signals don't end it anymore once they are watched.
+fn watch_signals(mut signals: Signals) -> watch::Receiver<bool> {
+    let (shutdown_tx, shutdown_rx) = watch::channel(false);
+    tokio::spawn(async move {
+        signals.recv().await;
+        let _ = shutdown_tx.send(true);
+        kill_after(SHUTDOWN_TIMEOUT);
+    });
+    shutdown_rx
+}
+
+/// Exits the process if it is still running after `timeout`, restoring the
+/// terminal first.
+fn kill_after(timeout: Duration) {
+    std::thread::spawn(move || {
+        std::thread::sleep(timeout);
+        // Nothing more can be done if this fails on the way out.
+        let _ = tui::restore();
+        std::process::exit(1);
+    });
+}
+
+/// SIGTERM, SIGHUP and SIGINT.
+#[cfg(unix)]
+#[derive(Debug)]
+struct Signals(Vec<tokio::signal::unix::Signal>);
+
+#[cfg(unix)]
+impl Signals {
+    fn new() -> Result<Self> {
+        use tokio::signal::unix::{signal, SignalKind};
+        let kinds = [
+            SignalKind::terminate(),
+            SignalKind::hangup(),
+            SignalKind::interrupt(),
+        ];
+        Ok(Self(
+            kinds.into_iter().map(signal).collect::<Result<_, _>>()?,
+        ))
+    }
+
+    async fn recv(&mut self) {
+        let received = self.0.iter_mut().map(|signal| Box::pin(signal.recv()));
+        futures_util::future::select_all(received).await;
+    }
+}
+
+/// Ctrl+Break and closing the console window.
+#[cfg(windows)]
+#[derive(Debug)]
+struct Signals {
+    ctrl_break: tokio::signal::windows::CtrlBreak,
+    ctrl_close: tokio::signal::windows::CtrlClose,
+}
+
+#[cfg(windows)]
+impl Signals {
+    fn new() -> Result<Self> {
+        Ok(Self {
+            ctrl_break: tokio::signal::windows::ctrl_break()?,
+            ctrl_close: tokio::signal::windows::ctrl_close()?,
+        })
+    }
+
+    async fn recv(&mut self) {
+        tokio::select! {
+            _ = self.ctrl_break.recv() => {}
+            _ = self.ctrl_close.recv() => {}
+        }
+    }
+}
+
+/// Takes what the stream gave into an [`Event`], or `None` for the ones we
+/// don't handle.
+fn convert(event: Option<io::Result<CrosstermEvent>>) -> Result<Option<Event>, EventError> {
+    let event = event
+        .ok_or(EventError::Disconnected)?
+        .map_err(EventError::Io)?;
+    Ok(match event {
+        // ignore KeyEventKind::Release on windows
+        CrosstermEvent::Key(e) if e.kind == KeyEventKind::Press => Some(Event::Key(e)),
+        CrosstermEvent::Mouse(e) => Some(Event::Mouse(e)),
+        CrosstermEvent::Resize(w, h) => Some(Event::Resize(w, h)),
+        _ => None,
+    })
+}
+
+/// Whether `key` moves a selection by a single row, so that repeats of it
+/// can be taken as one move.
+fn is_navigation(key: &KeyEvent) -> bool {
+    key.modifiers.is_empty()
+        && matches!(
+            key.code,
+            KeyCode::Up | KeyCode::Down | KeyCode::Char('j') | KeyCode::Char('k')
+        )
+}
+
+/// Takes runs of the same navigation key in `events` together, with the
+/// number of times it was pressed, so that a held-down key doesn't keep
+/// moving long after it was let go. Other events are kept one by one, and the
+/// ticks in between are taken as a single tick at the end.
+pub fn coalesce(events: impl IntoIterator<Item = Event>) -> Vec<(Event, usize)> {
+    let mut coalesced: Vec<(Event, usize)> = Vec::new();
+    let mut tick = false;
+    for event in events {
+        if let Event::Tick = event {
+            tick = true;
+            continue;
+        }
+        if let (Event::Key(key), Some((Event::Key(last), count))) = (&event, coalesced.last_mut()) {
+            if is_navigation(key) && key == last {
+                *count += 1;
+                continue;
+            }
+        }
+        coalesced.push((event, 1));
+    }
+    if tick {
+        coalesced.push((Event::Tick, 1));
+    }
+    coalesced
+}
+
+#[cfg(test)]
+mod tests {
+    use crossterm::event::KeyModifiers;
+
+    use super::*;
+
+    fn key(code: KeyCode) -> Event {
+        Event::Key(code.into())
+    }
+
+    fn keys(coalesced: &[(Event, usize)]) -> Vec<(Option<KeyEvent>, usize)> {
+        coalesced
+            .iter()
+            .map(|(event, count)| match event {
+                Event::Key(key) => (Some(*key), *count),
+                _ => (None, *count),
             })
+            .collect()
+    }
+
+    #[test]
+    fn test_coalesce() {
+        let events = [
+            key(KeyCode::Down),
+            key(KeyCode::Down),
+            Event::Tick,
+            key(KeyCode::Down),
+            key(KeyCode::Up),
+            key(KeyCode::Char('j')),
+            key(KeyCode::Char('x')),
+            key(KeyCode::Char('x')),
+            Event::Tick,
+            key(KeyCode::Char('j')),
+            Event::Key(KeyEvent::new(KeyCode::Char('j'), KeyModifiers::CONTROL)),
+            Event::Key(KeyEvent::new(KeyCode::Char('j'), KeyModifiers::CONTROL)),
+            Event::Resize(80, 24),
+            key(KeyCode::Char('j')),
+        ];
+        assert_eq!(
+            keys(&coalesce(events)),
+            vec![
+                (Some(KeyCode::Down.into()), 3),
+                (Some(KeyCode::Up.into()), 1),
+                (Some(KeyCode::Char('j').into()), 1),
+                (Some(KeyCode::Char('x').into()), 1),
+                (Some(KeyCode::Char('x').into()), 1),
+                (Some(KeyCode::Char('j').into()), 1),
+                (
+                    Some(KeyEvent::new(KeyCode::Char('j'), KeyModifiers::CONTROL)),
+                    1
+                ),
+                (
+                    Some(KeyEvent::new(KeyCode::Char('j'), KeyModifiers::CONTROL)),
+                    1
+                ),
+                (None, 1),
+                (Some(KeyCode::Char('j').into()), 1),
+                (None, 1),
+            ]
+        );
+        assert!(coalesce([]).is_empty());
+    }
+
+    #[test]
+    fn test_convert() {
+        let press = KeyEvent::from(KeyCode::Char('j'));
+        let release = KeyEvent {
+            kind: KeyEventKind::Release,
+            ..press
         };
-        Self {
-            sender,
-            receiver,
-            handler,
-        }
+        assert!(matches!(
+            convert(Some(Ok(CrosstermEvent::Key(press)))),
+            Ok(Some(Event::Key(key))) if key == press
+        ));
+        assert!(matches!(
+            convert(Some(Ok(CrosstermEvent::Key(release)))),
+            Ok(None)
+        ));
+        assert!(matches!(
+            convert(Some(Ok(CrosstermEvent::FocusGained))),
+            Ok(None)
+        ));
+        assert!(matches!(convert(None), Err(EventError::Disconnected)));
+        assert!(matches!(
+            convert(Some(Err(io::ErrorKind::BrokenPipe.into()))),
+            Err(EventError::Io(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_disconnected() {
+        let mut events =
+            EventHandler::with_stream(futures_util::stream::empty().boxed(), 1).unwrap();
+        // Skip the first tick, which is due right away.
+        events.next_tick += Duration::from_secs(1);
+        assert!(matches!(
+            events.next(None).await,
+            Err(EventError::Disconnected)
+        ));
+        assert!(matches!(
+            events.try_next().await,
+            Err(EventError::Disconnected)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_try_next() {
+        let pressed = [
+            Ok(CrosstermEvent::FocusGained),
+            Ok(CrosstermEvent::Key(KeyCode::Up.into())),
+        ];
+        let stream = futures_util::stream::iter(pressed).chain(futures_util::stream::pending());
+        let mut events = EventHandler::with_stream(stream.boxed(), 1).unwrap();
+        assert!(matches!(events.try_next().await, Ok(Some(Event::Key(_)))));
+        assert!(matches!(events.try_next().await, Ok(None)));
+
+        let failed = [Err(io::Error::other("read failed"))];
+        let stream = futures_util::stream::iter(failed).chain(futures_util::stream::pending());
+        let mut events = EventHandler::with_stream(stream.boxed(), 1).unwrap();
+        assert!(matches!(events.try_next().await, Err(EventError::Io(_))));
     }
 
-    /// Receive the next event from the handler thread.
-    ///
-    /// This function will always block the current thread if
-    /// there is no data available and it's possible for more data to be sent.
-    pub fn next(&self) -> Result<Event> {
-        Ok(self.receiver.recv()?)
+    #[tokio::test]
+    async fn test_spawn_fetch() {
+        let mut events =
+            EventHandler::with_stream(futures_util::stream::pending().boxed(), 1).unwrap();
+        events.next_tick += Duration::from_secs(1);
+        events.spawn_fetch(async {
+            Fetched {
+                profile: 1,
+                status: Err(color_eyre::eyre::eyre!("no status")),
+                metadata: None,
+                role: None,
+                duration: Duration::ZERO,
+            }
+        });
+        assert!(matches!(
+            events.next(None).await,
+            Ok(Event::Fetched(fetched)) if fetched.profile == 1
+        ));
     }
 }
@@ -0,0 +1,234 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    symbols::{border, scrollbar, Marker},
+    widgets::{Block, Borders, Scrollbar, ScrollbarOrientation},
+};
+
+/// Every non-ASCII character the UI draws on its own, so that terminals
+/// without UTF-8 can get ASCII stand-ins. Text from the server is shown as is.
+#[derive(Debug, PartialEq)]
+pub struct Glyphs {
+    pub ellipsis: &'static str,
+    pub fetching: &'static str,
+    pub paused: &'static str,
+    pub stalled: &'static str,
+    pub unreachable: &'static str,
+    pub category: &'static str,
+    pub lock: &'static str,
+    pub pin: &'static str,
+    /// In front of the stages of an expanded row.
+    pub stage: &'static str,
+    pub sort_ascending: &'static str,
+    pub sort_descending: &'static str,
+    pub increase: &'static str,
+    pub decrease: &'static str,
+    pub minus: &'static str,
+    /// Between key hints and links.
+    pub separator: &'static str,
+    pub dash: &'static str,
+    pub up_down: &'static str,
+    pub left_right: &'static str,
+    /// Sparkline bars, from lowest to highest.
+    pub bars: [char; 8],
+    pub tab_divider: &'static str,
+    pub border: border::Set,
+    pub rounded_border: border::Set,
+    pub scrollbar: scrollbar::Set,
+    /// Points of chart lines.
+    pub marker: Marker,
+}
+
+const ASCII_BORDER: border::Set = border::Set {
+    top_left: "+",
+    top_right: "+",
+    bottom_left: "+",
+    bottom_right: "+",
+    vertical_left: "|",
+    vertical_right: "|",
+    horizontal_top: "-",
+    horizontal_bottom: "-",
+};
+
+impl Glyphs {
+    pub const UNICODE: Glyphs = Glyphs {
+        ellipsis: "…",
+        fetching: "🔄 fetching…",
+        paused: "⏸ paused",
+        stalled: "⚠",
+        unreachable: "✗",
+        category: "▌",
+        lock: "🔒",
+        pin: "📌",
+        stage: "↳",
+        sort_ascending: "▲",
+        sort_descending: "▼",
+        increase: "▲",
+        decrease: "▼",
+        minus: "−",
+        separator: "·",
+        dash: "—",
+        up_down: "↑/↓",
+        left_right: "←/→",
+        bars: ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'],
+        tab_divider: "│",
+        border: border::PLAIN,
+        rounded_border: border::ROUNDED,
+        scrollbar: scrollbar::DOUBLE_VERTICAL,
+        marker: Marker::Braille,
+    };
+
+    pub const ASCII: Glyphs = Glyphs {
+        ellipsis: "...",
+        fetching: "fetching...",
+        paused: "paused",
+        stalled: "!",
+        unreachable: "x",
+        category: "|",
+        lock: "L",
+        pin: "*",
+        stage: "->",
+        sort_ascending: "^",
+        sort_descending: "v",
+        increase: "^",
+        decrease: "v",
+        minus: "-",
+        separator: "|",
+        dash: "-",
+        up_down: "Up/Down",
+        left_right: "Left/Right",
+        bars: ['_', '.', ':', '-', '=', '+', '*', '#'],
+        tab_divider: "|",
+        border: ASCII_BORDER,
+        rounded_border: ASCII_BORDER,
+        scrollbar: scrollbar::Set {
+            track: "|",
+            thumb: "#",
+            begin: "^",
+            end: "v",
+        },
+        marker: Marker::Dot,
+    };
+
+    pub fn new(ascii: bool) -> &'static Glyphs {
+        match ascii {
+            true => &Self::ASCII,
+            false => &Self::UNICODE,
+        }
+    }
+
+    /// A block with borders drawn in these glyphs.
+    pub fn block(&self) -> Block<'static> {
+        Block::default()
+            .borders(Borders::ALL)
+            .border_set(self.border)
+    }
+
+    /// Like [`Glyphs::block`], with rounded corners where available.
+    pub fn rounded_block(&self) -> Block<'static> {
+        Block::default()
+            .borders(Borders::ALL)
+            .border_set(self.rounded_border)
+    }
+
+    pub fn scrollbar(&self) -> Scrollbar<'static> {
+        Scrollbar::new(ScrollbarOrientation::VerticalRight).symbols(self.scrollbar.clone())
+    }
+
+    /// Swaps the characters drawn in `area` for ASCII ones if these are the
+    /// ASCII glyphs. Only for widgets like charts that don't let their
+    /// symbols be chosen, since any text in there gets mangled too.
+    pub fn replace_non_ascii(&self, buf: &mut Buffer, area: Rect) {
+        if *self != Self::ASCII {
+            return;
+        }
+        for position in area.positions() {
+            let cell = &mut buf[position];
+            let symbol = match cell.symbol() {
+                s if s.is_ascii() => continue,
+                "│" => "|",
+                "─" => "-",
+                "└" | "┌" | "┐" | "┘" => "+",
+                _ => "*",
+            };
+            cell.set_symbol(symbol);
+        }
+    }
+}
+
+/// Whether the locale asks for UTF-8, going by the same variables as
+/// `setlocale`. Without any of them set, the "C" locale applies, which isn't.
+pub fn locale_is_utf8() -> bool {
+    ["LC_ALL", "LC_CTYPE", "LANG"]
+        .into_iter()
+        .filter_map(|name| std::env::var(name).ok())
+        .find(|value| !value.is_empty())
+        .is_some_and(|locale| is_utf8(&locale))
+}
+
+fn is_utf8(locale: &str) -> bool {
+    let locale = locale.to_lowercase();
+    locale.contains("utf-8") || locale.contains("utf8")
+}
+
+#[cfg(test)]
+mod tests {
+    use unicode_width::UnicodeWidthStr;
+
+    use super::*;
+
+    #[test]
+    fn test_is_utf8() {
+        assert!(is_utf8("en_US.UTF-8"));
+        assert!(is_utf8("de_DE.utf8"));
+        assert!(!is_utf8("C"));
+        assert!(!is_utf8("POSIX"));
+        assert!(!is_utf8("en_US.ISO-8859-1"));
+    }
+
+    #[test]
+    fn test_ascii_glyphs() {
+        let g = Glyphs::ASCII;
+        let mut strs = vec![
+            g.ellipsis,
+            g.fetching,
+            g.paused,
+            g.stalled,
+            g.unreachable,
+            g.category,
+            g.lock,
+            g.stage,
+            g.sort_ascending,
+            g.sort_descending,
+            g.increase,
+            g.decrease,
+            g.minus,
+            g.separator,
+            g.dash,
+            g.up_down,
+            g.left_right,
+            g.tab_divider,
+            g.scrollbar.track,
+            g.scrollbar.thumb,
+            g.scrollbar.begin,
+            g.scrollbar.end,
+        ];
+        for border in [g.border, g.rounded_border] {
+            strs.extend([
+                border.top_left,
+                border.top_right,
+                border.bottom_left,
+                border.bottom_right,
+                border.vertical_left,
+                border.vertical_right,
+                border.horizontal_top,
+                border.horizontal_bottom,
+            ]);
+        }
+        for s in strs {
+            assert!(s.is_ascii(), "{:?} is not ASCII", s);
+            assert_eq!(s.width(), s.len());
+        }
+        assert!(g.bars.iter().all(char::is_ascii));
+    }
+}
@@ -0,0 +1,211 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    time::Instant,
+};
+
+use crate::models::{Status, Totals};
+
+/// Number of fetches kept per collection.
+pub const HISTORY_LEN: usize = 60;
+
+/// Number of fetches kept for the history tab by default.
+pub const DEFAULT_SAMPLES_LEN: usize = 720;
+
+/// Totals over all results of one fetch, `None` if the fetch failed.
+#[derive(Clone, Copy, Debug)]
+pub struct Sample {
+    pub time: Instant,
+    pub totals: Option<Totals>,
+}
+
+/// Pending task counts of recent fetches, keyed by result key so rows can be
+/// reordered or disappear without mixing up histories, and the totals of the
+/// fetches since the session or profile started.
+#[derive(Debug)]
+pub struct History {
+    pending: HashMap<String, VecDeque<u32>>,
+    samples: VecDeque<Sample>,
+    samples_len: usize,
+}
+
+impl Default for History {
+    fn default() -> Self {
+        Self::new(DEFAULT_SAMPLES_LEN)
+    }
+}
+
+impl History {
+    /// Keeps the totals of up to `samples_len` fetches.
+    pub fn new(samples_len: usize) -> Self {
+        Self {
+            pending: HashMap::new(),
+            samples: VecDeque::new(),
+            samples_len,
+        }
+    }
+
+    /// Appends the pending counts of a freshly fetched status, dropping the
+    /// histories of results that are no longer reported.
+    pub fn record(&mut self, status: &Status) {
+        self.record_sample(Instant::now(), Some(Totals::of(&status.results)));
+        let mut pending = HashMap::with_capacity(status.results.len());
+        for result in &status.results {
+            let key = result.key();
+            let mut values = self.pending.remove(&key).unwrap_or_default();
+            if values.len() == HISTORY_LEN {
+                values.pop_front();
+            }
+            values.push_back(result.pending);
+            pending.insert(key, values);
+        }
+        self.pending = pending;
+    }
+
+    /// Notes a failed fetch, leaving a gap in the totals.
+    pub fn record_failure(&mut self) {
+        if !self.samples.is_empty() {
+            self.record_sample(Instant::now(), None);
+        }
+    }
+
+    pub(crate) fn record_sample(&mut self, time: Instant, totals: Option<Totals>) {
+        if self.samples.len() == self.samples_len {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(Sample { time, totals });
+    }
+
+    pub fn pending(&self, key: &str) -> Option<&VecDeque<u32>> {
+        self.pending.get(key)
+    }
+
+    pub fn samples(&self) -> &VecDeque<Sample> {
+        &self.samples
+    }
+
+    /// Runs of `value` of the totals between failed fetches, as points of
+    /// seconds since the first sample and the value.
+    pub fn segments(&self, value: fn(&Totals) -> u64) -> Vec<Vec<(f64, f64)>> {
+        let Some(start) = self.samples.front().map(|sample| sample.time) else {
+            return Vec::new();
+        };
+        let mut segments = vec![Vec::new()];
+        for sample in &self.samples {
+            let x = sample.time.duration_since(start).as_secs_f64();
+            match (&sample.totals, segments.last_mut()) {
+                (Some(totals), Some(segment)) => segment.push((x, value(totals) as f64)),
+                (None, Some(segment)) if !segment.is_empty() => segments.push(Vec::new()),
+                _ => {}
+            }
+        }
+        segments.retain(|segment| !segment.is_empty());
+        segments
+    }
+
+    /// Forgets the pending counts of the results, returning how many there were.
+    pub fn clear_pending(&mut self) -> usize {
+        let len = self.pending.len();
+        self.pending.clear();
+        len
+    }
+
+    pub fn clear(&mut self) {
+        self.pending.clear();
+        self.samples.clear();
+    }
+}
+
+/// Renders the last `width` values as a line of `bars` scaled to their maximum.
+pub fn sparkline(values: &VecDeque<u32>, width: usize, bars: &[char; 8]) -> String {
+    let values: Vec<u32> = values.iter().rev().take(width).rev().copied().collect();
+    let max = values.iter().copied().max().unwrap_or_default();
+    values
+        .iter()
+        .map(|v| match max {
+            0 => bars[0],
+            max => bars[(*v as u64 * (bars.len() as u64 - 1) / max as u64) as usize],
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs::read_to_string, time::Duration};
+
+    use super::*;
+    use crate::glyphs::Glyphs;
+
+    fn test_status() -> Status {
+        let test = read_to_string("testdata/results.json").unwrap();
+        serde_json::from_str(&test).unwrap()
+    }
+
+    #[test]
+    fn test_record_keyed_by_collection() {
+        let mut history = History::default();
+        let mut status = test_status();
+        history.record(&status);
+
+        status.results.reverse();
+        status.results[0].pending = 5;
+        history.record(&status);
+        assert_eq!(history.pending("8194").unwrap(), &[829385, 5]);
+        assert_eq!(history.pending("94").unwrap(), &[0, 0]);
+
+        status.results.remove(0);
+        history.record(&status);
+        assert!(history.pending("8194").is_none());
+        assert_eq!(history.pending("94").unwrap(), &[0, 0, 0]);
+    }
+
+    #[test]
+    fn test_record_is_bounded() {
+        let mut history = History::default();
+        let status = test_status();
+        for _ in 0..HISTORY_LEN + 10 {
+            history.record(&status);
+        }
+        assert_eq!(history.pending("94").unwrap().len(), HISTORY_LEN);
+    }
+
+    #[test]
+    fn test_segments() {
+        let mut history = History::new(4);
+        assert!(history.segments(|t| t.pending).is_empty());
+        history.record_failure();
+        assert!(history.samples().is_empty());
+
+        let start = Instant::now();
+        let totals = |pending| Totals {
+            pending,
+            ..Totals::default()
+        };
+        for (seconds, pending) in [
+            (0, Some(8)),
+            (5, Some(6)),
+            (10, None),
+            (15, None),
+            (20, Some(3)),
+        ] {
+            history.record_sample(start + Duration::from_secs(seconds), pending.map(totals));
+        }
+        assert_eq!(history.samples().len(), 4);
+        assert_eq!(
+            history.segments(|t| t.pending),
+            vec![vec![(0.0, 6.0)], vec![(15.0, 3.0)]]
+        );
+
+        history.clear();
+        assert!(history.samples().is_empty());
+    }
+
+    #[test]
+    fn test_sparkline() {
+        let bars = &Glyphs::UNICODE.bars;
+        assert_eq!(sparkline(&VecDeque::new(), 5, bars), "");
+        assert_eq!(sparkline(&VecDeque::from([0, 0]), 5, bars), "▁▁");
+        assert_eq!(sparkline(&VecDeque::from([9, 0, 7, 14]), 3, bars), "▁▄█");
+        let bars = &Glyphs::ASCII.bars;
+        assert_eq!(sparkline(&VecDeque::from([9, 0, 7, 14]), 3, bars), "_-#");
+    }
+}
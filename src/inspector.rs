@@ -0,0 +1,255 @@
+use crate::ui::input::Input;
+
+/// The JSON of a single result, pretty-printed, with a scroll position and a
+/// search within it.
+#[derive(Clone, Debug, Default)]
+pub struct Inspector {
+    pub title: String,
+    pub lines: Vec<String>,
+    /// Index of the first line on screen.
+    pub scroll: usize,
+    pub search: Input,
+    /// Whether the search line has focus.
+    pub searching: bool,
+    /// Lines that fit on screen, set when rendering.
+    pub visible_lines: usize,
+}
+
+impl Inspector {
+    pub fn new(title: String, value: &serde_json::Value) -> Self {
+        let json = serde_json::to_string_pretty(value).unwrap_or_default();
+        Self {
+            title,
+            lines: json.lines().map(str::to_string).collect(),
+            ..Self::default()
+        }
+    }
+
+    fn max_scroll(&self) -> usize {
+        self.lines.len().saturating_sub(self.visible_lines.max(1))
+    }
+
+    /// Scrolls by `lines`, up when negative, staying within the document.
+    pub fn scroll_by(&mut self, lines: isize) {
+        self.scroll = self
+            .scroll
+            .saturating_add_signed(lines)
+            .min(self.max_scroll());
+    }
+
+    pub fn page_up(&mut self) {
+        self.scroll_by(-(self.visible_lines.max(1) as isize));
+    }
+
+    pub fn page_down(&mut self) {
+        self.scroll_by(self.visible_lines.max(1) as isize);
+    }
+
+    pub fn top(&mut self) {
+        self.scroll = 0;
+    }
+
+    pub fn bottom(&mut self) {
+        self.scroll = self.max_scroll();
+    }
+
+    /// The lowercase search query, empty when not searching.
+    pub fn query(&self) -> String {
+        self.search.value().to_lowercase()
+    }
+
+    /// Indices of the lines containing the query.
+    pub fn matches(&self) -> Vec<usize> {
+        let query = self.query();
+        if query.is_empty() {
+            return Vec::new();
+        }
+        self.lines
+            .iter()
+            .enumerate()
+            .filter(|(_, line)| line.to_lowercase().contains(&query))
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    /// Scrolls the first match at or below the top line to the top, wrapping
+    /// around to the first match of the document.
+    pub fn first_match(&mut self) {
+        let matches = self.matches();
+        if let Some(line) = matches
+            .iter()
+            .find(|line| **line >= self.scroll)
+            .or(matches.first())
+        {
+            self.scroll = *line;
+        }
+    }
+
+    /// Scrolls the match after the top line to the top, wrapping around.
+    pub fn next_match(&mut self) {
+        let matches = self.matches();
+        if let Some(line) = matches
+            .iter()
+            .find(|line| **line > self.scroll)
+            .or(matches.first())
+        {
+            self.scroll = *line;
+        }
+    }
+
+    /// Scrolls the match before the top line to the top, wrapping around.
+    pub fn previous_match(&mut self) {
+        let matches = self.matches();
+        if let Some(line) = matches
+            .iter()
+            .rev()
+            .find(|line| **line < self.scroll)
+            .or(matches.last())
+        {
+            self.scroll = *line;
+        }
+    }
+}
+
+/// What a piece of a pretty-printed JSON line is, for tinting it.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum JsonToken {
+    Key,
+    String,
+    Number,
+    /// `true`, `false` or `null`.
+    Literal,
+    /// Brackets, commas, colons and whitespace.
+    Punctuation,
+}
+
+/// Splits a line of pretty-printed JSON into pieces, each as a byte range.
+pub fn tokenize(line: &str) -> Vec<(JsonToken, std::ops::Range<usize>)> {
+    let bytes = line.as_bytes();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        let start = i;
+        let token = match bytes[i] {
+            b'"' => {
+                i += 1;
+                while i < bytes.len() && bytes[i] != b'"' {
+                    i += if bytes[i] == b'\\' { 2 } else { 1 };
+                }
+                i = (i + 1).min(bytes.len());
+                match line[i..].trim_start().starts_with(':') {
+                    true => JsonToken::Key,
+                    false => JsonToken::String,
+                }
+            }
+            b'-' | b'0'..=b'9' => {
+                while i < bytes.len()
+                    && matches!(bytes[i], b'-' | b'+' | b'.' | b'e' | b'E' | b'0'..=b'9')
+                {
+                    i += 1;
+                }
+                JsonToken::Number
+            }
+            b'a'..=b'z' => {
+                while i < bytes.len() && bytes[i].is_ascii_lowercase() {
+                    i += 1;
+                }
+                JsonToken::Literal
+            }
+            _ => {
+                while i < bytes.len()
+                    && !matches!(bytes[i], b'"' | b'-' | b'0'..=b'9' | b'a'..=b'z')
+                {
+                    i += 1;
+                }
+                JsonToken::Punctuation
+            }
+        };
+        tokens.push((token, start..i));
+    }
+    tokens
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn inspector() -> Inspector {
+        let mut inspector = Inspector::new(
+            "test".to_string(),
+            &json!({
+                "finished": 3,
+                "collection": {"label": "Leaks", "foreign_id": "leak_1"},
+                "stages": [{"job_id": "leak_2", "stage": "index"}],
+            }),
+        );
+        inspector.visible_lines = 4;
+        inspector
+    }
+
+    #[test]
+    fn test_scroll() {
+        let mut inspector = inspector();
+        assert_eq!(inspector.lines.len(), 13);
+        assert_eq!(inspector.lines[1], r#"  "collection": {"#);
+        inspector.scroll_by(-1);
+        assert_eq!(inspector.scroll, 0);
+        inspector.page_down();
+        assert_eq!(inspector.scroll, 4);
+        inspector.bottom();
+        assert_eq!(inspector.scroll, 9);
+        inspector.scroll_by(3);
+        assert_eq!(inspector.scroll, 9);
+        inspector.page_up();
+        assert_eq!(inspector.scroll, 5);
+        inspector.top();
+        assert_eq!(inspector.scroll, 0);
+    }
+
+    #[test]
+    fn test_search() {
+        let mut inspector = inspector();
+        inspector.first_match();
+        assert_eq!(inspector.scroll, 0);
+        "LEAK".chars().for_each(|c| inspector.search.insert(c));
+        assert_eq!(inspector.matches(), vec![2, 3, 8]);
+        inspector.first_match();
+        assert_eq!(inspector.scroll, 2);
+        inspector.next_match();
+        assert_eq!(inspector.scroll, 3);
+        inspector.next_match();
+        inspector.next_match();
+        assert_eq!(inspector.scroll, 2);
+        inspector.previous_match();
+        assert_eq!(inspector.scroll, 8);
+    }
+
+    #[test]
+    fn test_tokenize() {
+        let line = r#"  "label": "a \"b\": c", "n": -1.5e3, "ok": null"#;
+        let tokens: Vec<(JsonToken, &str)> = tokenize(line)
+            .into_iter()
+            .map(|(token, range)| (token, &line[range]))
+            .collect();
+        assert_eq!(
+            tokens,
+            vec![
+                (JsonToken::Punctuation, "  "),
+                (JsonToken::Key, r#""label""#),
+                (JsonToken::Punctuation, ": "),
+                (JsonToken::String, r#""a \"b\": c""#),
+                (JsonToken::Punctuation, ", "),
+                (JsonToken::Key, r#""n""#),
+                (JsonToken::Punctuation, ": "),
+                (JsonToken::Number, "-1.5e3"),
+                (JsonToken::Punctuation, ", "),
+                (JsonToken::Key, r#""ok""#),
+                (JsonToken::Punctuation, ": "),
+                (JsonToken::Literal, "null"),
+            ]
+        );
+        assert_eq!(tokenize(r#""unterminated\"#).len(), 1);
+    }
+}
@@ -1,16 +1,28 @@
 #![deny(clippy::unwrap_used)]
 
+pub mod action;
 pub mod app;
+pub mod client;
+pub mod clipboard;
 pub mod event;
+pub mod glyphs;
+pub mod history;
+pub mod inspector;
+pub mod metrics;
 pub mod models;
+pub mod sort;
+pub mod state;
+pub mod theme;
 pub mod tui;
 pub mod ui;
 pub mod update;
 
+use std::time::Duration;
+
 use app::App;
 
 use color_eyre::Result;
-use event::{Event, EventHandler};
+use event::{Event, EventError, EventHandler};
 use ratatui::prelude::{CrosstermBackend, Terminal};
 use tui::Tui;
 
@@ -40,26 +52,70 @@ async fn main() -> Result<()> {
         std::process::exit(0);
     };
 
-    app.fetch()
-        .await
-        .unwrap_or_else(|e| app.error_message = e.to_string());
+    update::refresh(&mut app).await;
 
     let backend = CrosstermBackend::new(std::io::stderr());
     let terminal = Terminal::new(backend)?;
-    let events = EventHandler::new(50);
+    let events = EventHandler::new(app.config.frame_rate)?;
     let mut tui = Tui::new(terminal, events);
+    tui::install_panic_hook();
     tui.enter()?;
 
-    while !app.should_quit {
-        tui.draw(&mut app)?;
-        match tui.events.next()? {
-            Event::Tick => update::fetch(&mut app).await,
-            Event::Key(key_event) => update::update(&mut app, key_event).await,
-            Event::Mouse(_) => {}
-            Event::Resize(_, _) => {}
+    let mut disconnected = false;
+    while !app.should_quit && !tui.events.shutdown_requested() {
+        if app.dirty {
+            tui.draw(&mut app)?;
+        }
+        let events = match next_events(&mut tui.events, app.fetch_due_in()).await {
+            Ok(events) => events,
+            Err(EventError::Disconnected) => {
+                disconnected = true;
+                break;
+            }
+            Err(e) => return Err(e.into()),
         };
+        for (event, count) in event::coalesce(events) {
+            // Ticks tell for themselves whether there is anything to draw.
+            if !matches!(event, Event::Tick) {
+                app.dirty = true;
+            }
+            match event {
+                Event::Tick => app.tick(),
+                Event::Fetch => {
+                    if let Some(fetch) = update::fetch(&mut app) {
+                        tui.events.spawn_fetch(fetch);
+                    }
+                }
+                Event::Fetched(fetched) => update::fetched(&mut app, *fetched),
+                Event::Key(key_event) => update::update_repeated(&mut app, key_event, count).await,
+                Event::Mouse(mouse_event) => update::mouse(&mut app, mouse_event).await,
+                Event::Resize(_, _) => app.resized(),
+                Event::Shutdown => app.should_quit = true,
+            };
+            if app.should_quit || tui.events.shutdown_requested() {
+                break;
+            }
+        }
     }
 
-    tui.exit()?;
-    Ok(())
+    let exited = tui.exit();
+    if disconnected {
+        // Restoring a terminal that is gone may well fail, never mind that.
+        eprintln!("aleph-tui: {}, exiting", EventError::Disconnected);
+        return Ok(());
+    }
+    exited
+}
+
+/// Waits for the next event and takes whatever else came in while drawing,
+/// to handle all of it before drawing again.
+async fn next_events(
+    events: &mut EventHandler,
+    fetch_in: Option<Duration>,
+) -> Result<Vec<Event>, EventError> {
+    let mut next = vec![events.next(fetch_in).await?];
+    while let Some(event) = events.try_next().await? {
+        next.push(event);
+    }
+    Ok(next)
 }
@@ -0,0 +1,60 @@
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+
+/// Measurements of the fetch and draw paths, for the debug overlay.
+#[derive(Debug, Default)]
+pub struct Metrics {
+    /// How long the last fetch took, all requests included.
+    pub fetch_duration: Option<Duration>,
+    /// Size of the last status response body.
+    pub bytes_received: Option<usize>,
+    /// How long parsing the last status response took.
+    pub deserialize_duration: Option<Duration>,
+    /// Request ID the server gave the last status response, if any.
+    pub request_id: Option<String>,
+    /// Rows of the collections table built by the last render.
+    pub rows_rendered: usize,
+    /// Time spent in `ui::render` by the last draw.
+    pub render_duration: Option<Duration>,
+    /// The last draw as a whole, including writing to the terminal.
+    pub draw_duration: Option<Duration>,
+    /// Times of the ticks within the last second.
+    ticks: VecDeque<Instant>,
+}
+
+impl Metrics {
+    pub fn record_tick(&mut self, now: Instant) {
+        while self
+            .ticks
+            .front()
+            .is_some_and(|tick| now.duration_since(*tick) >= Duration::from_secs(1))
+        {
+            self.ticks.pop_front();
+        }
+        self.ticks.push_back(now);
+    }
+
+    pub fn ticks_per_second(&self) -> usize {
+        self.ticks.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ticks_per_second() {
+        let mut metrics = Metrics::default();
+        assert_eq!(metrics.ticks_per_second(), 0);
+        let start = Instant::now();
+        for i in 0..30 {
+            metrics.record_tick(start + Duration::from_millis(50 * i));
+        }
+        assert_eq!(metrics.ticks_per_second(), 20);
+        metrics.record_tick(start + Duration::from_secs(10));
+        assert_eq!(metrics.ticks_per_second(), 1);
+    }
+}
@@ -1,8 +1,12 @@
-use std::fmt::Display;
+use std::{cmp::Ordering, fmt::Display, sync::Arc};
 
+use chrono::{NaiveDateTime, TimeDelta};
+use itertools::Itertools;
 use serde::Deserialize;
 
-#[derive(Clone, Debug, Deserialize)]
+/// Links the server sent along with a collection, empty if it left one out.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
 pub struct Links {
     #[serde(alias = "self")]
     pub self_: String,
@@ -11,26 +15,153 @@ pub struct Links {
     pub ui: String,
 }
 
-#[derive(Clone, Debug, Deserialize)]
+/// Kind of data in a collection, as assigned in Aleph.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, Hash, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Category {
+    Casefile,
+    News,
+    Leak,
+    Land,
+    Gazette,
+    Court,
+    Company,
+    Sanctions,
+    Procurement,
+    Finance,
+    Grey,
+    Library,
+    License,
+    Regulatory,
+    Poi,
+    Customs,
+    Census,
+    Transport,
+    #[default]
+    #[serde(other)]
+    Other,
+}
+
+impl Category {
+    pub const ALL: [Category; 19] = [
+        Category::Casefile,
+        Category::News,
+        Category::Leak,
+        Category::Land,
+        Category::Gazette,
+        Category::Court,
+        Category::Company,
+        Category::Sanctions,
+        Category::Procurement,
+        Category::Finance,
+        Category::Grey,
+        Category::Library,
+        Category::License,
+        Category::Regulatory,
+        Category::Poi,
+        Category::Customs,
+        Category::Census,
+        Category::Transport,
+        Category::Other,
+    ];
+}
+
+impl Display for Category {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Category::Casefile => "Personal investigation",
+            Category::News => "News archives",
+            Category::Leak => "Leaks",
+            Category::Land => "Land registry",
+            Category::Gazette => "Gazettes",
+            Category::Court => "Court archives",
+            Category::Company => "Company registries",
+            Category::Sanctions => "Sanctions lists",
+            Category::Procurement => "Procurement",
+            Category::Finance => "Financial records",
+            Category::Grey => "Grey literature",
+            Category::Library => "Document libraries",
+            Category::License => "Licenses and concessions",
+            Category::Regulatory => "Regulatory filings",
+            Category::Poi => "Persons of interest",
+            Category::Customs => "Customs declarations",
+            Category::Census => "Population census",
+            Category::Transport => "Air and maritime registers",
+            Category::Other => "Other material",
+        };
+        f.write_str(name)
+    }
+}
+
+/// A collection as embedded in status results. Fields left out by the server
+/// get their defaults.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
 pub struct Collection {
-    pub created_at: String,
-    pub updated_at: String,
-    pub category: String,
-    pub frequency: String,
+    pub created_at: Option<String>,
+    pub updated_at: Option<String>,
+    pub category: Category,
+    /// How often the data gets updated, like "weekly".
+    pub frequency: Option<String>,
     pub collection_id: String,
     pub foreign_id: String,
-    pub data_updated_at: String,
+    pub data_updated_at: Option<String>,
     pub label: String,
+    pub languages: Vec<String>,
     pub casefile: bool,
     pub secret: bool,
     pub xref: Option<bool>,
     pub restricted: Option<bool>,
     pub id: String,
     pub writeable: bool,
-    pub links: Links,
+    pub links: Option<Links>,
     pub shallow: bool,
 }
 
+impl Collection {
+    /// The `created_at` timestamp (naive UTC), if present and well-formed.
+    pub fn created_time(&self) -> Option<NaiveDateTime> {
+        parse_timestamp(self.created_at.as_ref()?)
+    }
+
+    /// The `data_updated_at` timestamp (naive UTC), if present and well-formed.
+    pub fn data_updated_time(&self) -> Option<NaiveDateTime> {
+        parse_timestamp(self.data_updated_at.as_ref()?)
+    }
+
+    /// Whether access to the collection is limited, being secret (not
+    /// public) or restricted (marked as sensitive).
+    pub fn is_locked(&self) -> bool {
+        self.secret || self.restricted == Some(true)
+    }
+
+    /// Describes the update frequency, `None` if it is unknown.
+    pub fn frequency_label(&self) -> Option<String> {
+        match self.frequency.as_deref()? {
+            "" | "unknown" => None,
+            "never" => Some("not updated".to_string()),
+            frequency => Some(format!("updated {}", frequency)),
+        }
+    }
+}
+
+/// Share of finished tasks among all known tasks, `None` when there are no tasks.
+pub fn progress(finished: u32, running: u32, pending: u32) -> Option<f64> {
+    let total = finished as u64 + running as u64 + pending as u64;
+    match total {
+        0 => None,
+        total => Some(finished as f64 / total as f64),
+    }
+}
+
+/// Formats a progress ratio as a whole percentage, never rounding up to 100%.
+pub fn format_progress(progress: Option<f64>) -> String {
+    match progress {
+        Some(ratio) => format!("{}%", (ratio * 100.0).floor()),
+        None => "idle".to_string(),
+    }
+}
+
 #[derive(Clone, Debug, Deserialize)]
 pub struct Stage {
     pub job_id: String,
@@ -38,18 +169,59 @@ pub struct Stage {
     pub finished: u32,
     pub running: u32,
     pub pending: u32,
+    /// Per-task breakdown of the stage, not reported by every Aleph version.
+    #[serde(default)]
+    pub tasks: Vec<Task>,
+}
+
+/// Counters for one task type (e.g. `ingest.pdf`) within a stage.
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct Task {
+    pub name: String,
+    #[serde(default)]
+    pub todo: u32,
+    #[serde(default)]
+    pub doing: u32,
+    #[serde(default)]
+    pub succeeded: u32,
+    #[serde(default)]
+    pub failed: u32,
+    /// Seconds spent on this task so far.
+    #[serde(default)]
+    pub took: Option<f64>,
 }
 
 impl Display for Stage {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "{:<10} finished: {:<7} running {:<7} pending {:<7}",
-            self.stage, self.finished, self.running, self.pending
+            "{:<10} finished: {:<7} running {:<7} pending {:<7} {:>4}",
+            self.stage,
+            self.finished,
+            self.running,
+            self.pending,
+            format_progress(self.progress())
         )
     }
 }
 
+impl Stage {
+    pub fn progress(&self) -> Option<f64> {
+        progress(self.finished, self.running, self.pending)
+    }
+}
+
+/// Parses a server timestamp (naive UTC), with or without fractional seconds.
+fn parse_timestamp(timestamp: &str) -> Option<NaiveDateTime> {
+    NaiveDateTime::parse_from_str(timestamp, "%Y-%m-%dT%H:%M:%S%.f").ok()
+}
+
+/// Converts a number of seconds from the server, `None` when it is out of
+/// range.
+pub fn seconds_delta(seconds: f64) -> Option<TimeDelta> {
+    TimeDelta::try_seconds(seconds as i64).filter(|_| seconds.is_finite())
+}
+
 #[derive(Clone, Deserialize, Debug)]
 #[serde(untagged)]
 pub enum StageOrStages {
@@ -62,6 +234,22 @@ pub struct StatusResult {
     pub finished: u32,
     pub running: u32,
     pub pending: u32,
+    #[serde(default)]
+    pub failed: u32,
+    #[serde(default)]
+    pub aborted: u32,
+    /// Estimated seconds until the job is done, not reported by every Aleph version.
+    #[serde(default)]
+    pub remaining_time: Option<f64>,
+    /// When the first task of the job was seen, not reported by every Aleph version.
+    #[serde(default)]
+    pub min_ts: Option<String>,
+    /// When the job last saw any activity, not reported by every Aleph version.
+    #[serde(default)]
+    pub max_ts: Option<String>,
+    /// Seconds spent on the job so far, not reported by every Aleph version.
+    #[serde(default)]
+    pub took: Option<f64>,
     pub start_time: Option<String>,
     pub end_time: Option<String>,
     pub last_update: Option<String>,
@@ -69,32 +257,300 @@ pub struct StatusResult {
     pub stages: Option<StageOrStages>,
 }
 
+impl StatusResult {
+    pub fn progress(&self) -> Option<f64> {
+        progress(self.finished, self.running, self.pending)
+    }
+
+    /// Estimated time until the job is done, `None` when the server gave no
+    /// usable estimate.
+    pub fn eta(&self) -> Option<TimeDelta> {
+        self.remaining_time
+            .filter(|seconds| *seconds >= 1.0)
+            .and_then(seconds_delta)
+    }
+
+    /// The `last_update` timestamp (naive UTC), if present and well-formed.
+    pub fn last_update_time(&self) -> Option<NaiveDateTime> {
+        parse_timestamp(self.last_update.as_ref()?)
+    }
+
+    /// The `min_ts` timestamp (naive UTC), if present and well-formed.
+    pub fn started_time(&self) -> Option<NaiveDateTime> {
+        parse_timestamp(self.min_ts.as_ref()?)
+    }
+
+    /// The `max_ts` timestamp (naive UTC), if present and well-formed.
+    pub fn last_activity_time(&self) -> Option<NaiveDateTime> {
+        parse_timestamp(self.max_ts.as_ref()?)
+    }
+
+    /// Time spent on the job so far, `None` when not reported.
+    pub fn took(&self) -> Option<TimeDelta> {
+        self.took
+            .filter(|seconds| *seconds >= 0.0)
+            .and_then(seconds_delta)
+    }
+
+    /// Whether tasks are running but nothing was updated for longer than
+    /// `threshold`. Results without a `last_update` are never stalled.
+    pub fn is_stalled(&self, now: NaiveDateTime, threshold: TimeDelta) -> bool {
+        self.running > 0
+            && self
+                .last_update_time()
+                .is_some_and(|last_update| now - last_update > threshold)
+    }
+
+    /// The stages (queues) of the result, sorted by name.
+    pub fn stage_list(&self) -> Vec<&Stage> {
+        match &self.stages {
+            Some(StageOrStages::Stage(stage)) => vec![stage],
+            Some(StageOrStages::Stages(stages)) => {
+                stages.iter().sorted_by_key(|s| &s.stage).collect()
+            }
+            None => Vec::new(),
+        }
+    }
+
+    /// All tasks of all stages, paired with their stage, in stage order.
+    pub fn tasks(&self) -> Vec<(&Stage, &Task)> {
+        self.stage_list()
+            .into_iter()
+            .flat_map(|stage| stage.tasks.iter().map(move |task| (stage, task)))
+            .collect()
+    }
+
+    /// Whether nothing is running or waiting to run.
+    pub fn is_idle(&self) -> bool {
+        self.running == 0 && self.pending == 0
+    }
+
+    pub fn category(&self) -> Option<Category> {
+        self.collection.as_ref().map(|c| c.category)
+    }
+
+    /// Failed and aborted tasks combined.
+    pub fn failures(&self) -> u32 {
+        self.failed + self.aborted
+    }
+
+    /// The collection label, or the list of stages for results without a collection.
+    pub fn label(&self) -> String {
+        match &self.collection {
+            Some(c) => c.label.to_string(),
+            None => match &self.stages {
+                Some(StageOrStages::Stage(s)) => s.stage.to_string(),
+                Some(StageOrStages::Stages(v)) => {
+                    v.iter().map(|s| s.stage.to_string() + ", ").collect()
+                }
+                None => "".to_string(),
+            },
+        }
+    }
+
+    /// Identifies a result across fetches: the collection id, or the job and
+    /// stage names for results without a collection.
+    pub fn key(&self) -> String {
+        match (&self.collection, &self.stages) {
+            (Some(c), _) => c.id.to_string(),
+            (None, Some(StageOrStages::Stage(s))) => format!("{}/{}", s.job_id, s.stage),
+            (None, Some(StageOrStages::Stages(v))) => v
+                .iter()
+                .map(|s| format!("{}/{}", s.job_id, s.stage))
+                .join(","),
+            (None, None) => String::default(),
+        }
+    }
+
+    /// Whether the label, collection id or foreign id contains `query`, which
+    /// is expected to be lowercase.
+    pub fn matches(&self, query: &str) -> bool {
+        if query.is_empty() {
+            return true;
+        }
+        let label_matches = self.label().to_lowercase().contains(query);
+        match &self.collection {
+            Some(c) => {
+                label_matches
+                    || c.id.to_lowercase().contains(query)
+                    || c.foreign_id.to_lowercase().contains(query)
+            }
+            None => label_matches,
+        }
+    }
+
+    /// Orders results by numeric collection id, with results that have no
+    /// collection last.
+    pub fn cmp_id(&self, other: &Self) -> Ordering {
+        match (&self.collection, &other.collection) {
+            (Some(a), Some(b)) => match (a.id.parse::<u64>(), b.id.parse::<u64>()) {
+                (Ok(x), Ok(y)) => x.cmp(&y),
+                _ => a.id.cmp(&b.id),
+            },
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => self.key().cmp(&other.key()),
+        }
+    }
+}
+
+/// Task counts summed over several results.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Totals {
+    pub finished: u64,
+    pub running: u64,
+    pub pending: u64,
+    pub failed: u64,
+}
+
+impl Totals {
+    pub fn of<'a>(results: impl IntoIterator<Item = &'a StatusResult>) -> Self {
+        results.into_iter().fold(Self::default(), |totals, r| Self {
+            finished: totals.finished + r.finished as u64,
+            running: totals.running + r.running as u64,
+            pending: totals.pending + r.pending as u64,
+            failed: totals.failed + r.failures() as u64,
+        })
+    }
+
+    pub fn progress(&self) -> Option<f64> {
+        let total = self.finished + self.running + self.pending;
+        match total {
+            0 => None,
+            total => Some(self.finished as f64 / total as f64),
+        }
+    }
+}
+
 #[derive(Debug, Default, Deserialize, Clone)]
 pub struct Status {
     pub results: Vec<StatusResult>,
     pub total: u32,
+    /// The response as the server sent it, for inspecting fields not
+    /// modelled here. Only set by [`Status::from_json`].
+    #[serde(skip)]
+    pub raw: Option<Arc<str>>,
 }
 
-#[derive(Debug, Default, Deserialize, Clone)]
+impl Status {
+    /// Parses a status response, keeping the JSON alongside.
+    pub fn from_json(body: &[u8]) -> serde_json::Result<Status> {
+        let mut status: Status = serde_json::from_slice(body)?;
+        status.raw = std::str::from_utf8(body).ok().map(Arc::from);
+        Ok(status)
+    }
+
+    /// The JSON the server sent for `result`, `None` if it isn't one of the
+    /// results of the response kept by [`Status::from_json`].
+    pub fn raw_result(&self, result: &StatusResult) -> Option<serde_json::Value> {
+        let mut value: serde_json::Value = serde_json::from_str(self.raw.as_deref()?).ok()?;
+        let key = result.key();
+        value["results"]
+            .as_array_mut()?
+            .iter_mut()
+            .find(|raw| StatusResult::deserialize(&**raw).is_ok_and(|raw| raw.key() == key))
+            .map(serde_json::Value::take)
+    }
+
+    /// Puts the results into an order that doesn't depend on how the server
+    /// listed them: collections by id, then results without a collection by
+    /// job id.
+    pub fn sort_results(&mut self) {
+        self.results
+            .sort_by_cached_key(|result| match &result.collection {
+                Some(collection) => (
+                    false,
+                    collection.id.parse().unwrap_or(u64::MAX),
+                    result.key(),
+                ),
+                None => (true, 0, result.key()),
+            });
+    }
+
+    /// The status in `testdata/results.json`.
+    #[cfg(test)]
+    pub(crate) fn test_data() -> Status {
+        let test = std::fs::read_to_string("testdata/results.json").unwrap();
+        Status::from_json(test.as_bytes()).unwrap()
+    }
+}
+
+#[derive(Debug, Default, Deserialize, Clone, PartialEq)]
 pub struct MetadataApp {
     pub title: Option<String>,
     pub version: Option<String>,
     pub ftm_version: Option<String>,
 }
 
-#[derive(Debug, Default, Deserialize, Clone)]
+#[derive(Debug, Default, Deserialize, Clone, PartialEq)]
 pub struct Metadata {
     pub status: String,
     pub maintenance: bool,
     pub app: MetadataApp,
 }
 
+/// The role (user) the API token belongs to.
+#[derive(Debug, Default, Deserialize, Clone, PartialEq)]
+pub struct Role {
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub email: Option<String>,
+}
+
+impl Role {
+    /// Name of the role, falling back to its email address.
+    pub fn display_name(&self) -> &str {
+        self.name
+            .as_deref()
+            .filter(|name| !name.is_empty())
+            .or(self.email.as_deref())
+            .unwrap_or("unknown")
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::fs::read_to_string;
 
     use super::*;
 
+    #[test]
+    fn test_sort_results() {
+        let test = read_to_string("testdata/results.json").unwrap();
+        let mut status: Status = serde_json::from_str(&test).unwrap();
+        let mut without_collection = status.results[0].clone();
+        without_collection.collection = None;
+        status.results.insert(0, without_collection);
+        status.results.reverse();
+
+        status.sort_results();
+        let keys: Vec<String> = status.results.iter().map(StatusResult::key).collect();
+        assert_eq!(keys[..2], ["94", "8194"]);
+        assert!(status.results[2].collection.is_none());
+    }
+
+    #[test]
+    fn test_from_json() {
+        let test = read_to_string("testdata/results.json").unwrap();
+        let mut status = Status::from_json(test.as_bytes()).unwrap();
+        assert_eq!(status.results.len(), 2);
+        status.results.reverse();
+        for result in &status.results {
+            let raw = status.raw_result(result).unwrap();
+            assert_eq!(
+                raw["collection"]["id"].as_str(),
+                result.collection.as_ref().map(|c| c.id.as_str())
+            );
+        }
+        let mut other = status.results[0].clone();
+        other.collection.as_mut().unwrap().id = "1".to_string();
+        assert_eq!(status.raw_result(&other), None);
+        status.raw = None;
+        assert_eq!(status.raw_result(&status.results[0]), None);
+        assert!(Status::from_json(b"{\"results\": 1}").is_err());
+    }
+
     #[test]
     fn test_status_deserialization() {
         let test = read_to_string("testdata/results.json").unwrap();
@@ -113,6 +569,102 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_tasks() {
+        let test = read_to_string("testdata/results_tasks.json").unwrap();
+        let status: Status = serde_json::from_str(&test).unwrap();
+        let tasks = status.results[0].tasks();
+        let names: Vec<_> = tasks
+            .iter()
+            .map(|(stage, task)| format!("{}/{}", stage.stage, task.name))
+            .collect();
+        assert_eq!(
+            names,
+            [
+                "analyze/ingest.pdf",
+                "analyze/ingest.docx",
+                "index/index.entities"
+            ]
+        );
+        assert_eq!(tasks[0].1.failed, 3);
+        assert_eq!(tasks[1].1.took, None);
+
+        let test = read_to_string("testdata/results.json").unwrap();
+        let status: Status = serde_json::from_str(&test).unwrap();
+        assert!(status.results[0].tasks().is_empty());
+    }
+
+    #[test]
+    fn test_job_timestamps() {
+        let result: StatusResult = serde_json::from_str(
+            r#"{"finished": 1, "running": 0, "pending": 0,
+                "min_ts": "2024-01-10T11:00:00", "max_ts": "2024-01-10T13:40:04.516229",
+                "took": 9604.5}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            result.started_time().unwrap().to_string(),
+            "2024-01-10 11:00:00"
+        );
+        assert!(result.last_activity_time().is_some());
+        assert_eq!(result.took(), Some(TimeDelta::seconds(9604)));
+
+        let result: StatusResult =
+            serde_json::from_str(r#"{"finished": 1, "running": 0, "pending": 0, "min_ts": null}"#)
+                .unwrap();
+        assert_eq!(result.started_time(), None);
+        assert_eq!(result.last_activity_time(), None);
+        assert_eq!(result.took(), None);
+
+        let result: StatusResult =
+            serde_json::from_str(r#"{"finished": 1, "running": 0, "pending": 0, "took": 1e300}"#)
+                .unwrap();
+        assert_eq!(result.took(), None);
+    }
+
+    #[test]
+    fn test_role_display_name() {
+        let role: Role =
+            serde_json::from_str(r#"{"id": "3", "name": "Chris", "email": "chris@example.org"}"#)
+                .unwrap();
+        assert_eq!(role.display_name(), "Chris");
+        let role: Role =
+            serde_json::from_str(r#"{"name": "", "email": "chris@example.org"}"#).unwrap();
+        assert_eq!(role.display_name(), "chris@example.org");
+        assert_eq!(Role::default().display_name(), "unknown");
+    }
+
+    #[test]
+    fn test_collection_deserialization() {
+        let collection: Collection =
+            serde_json::from_str(r#"{"collection_id": "7", "label": "Minimal"}"#).unwrap();
+        assert_eq!(collection.category, Category::Other);
+        assert!(collection.languages.is_empty());
+        assert_eq!(collection.created_time(), None);
+        assert_eq!(collection.frequency_label(), None);
+        assert!(!collection.is_locked());
+
+        let collection: Collection = serde_json::from_str(
+            r#"{"collection_id": "7", "label": "Full", "frequency": "weekly",
+                "languages": ["de", "en"], "created_at": "2024-01-04T13:56:09.023024"}"#,
+        )
+        .unwrap();
+        assert_eq!(collection.frequency_label().unwrap(), "updated weekly");
+        assert_eq!(collection.languages, ["de", "en"]);
+        assert!(collection.created_time().is_some());
+
+        let collection: Collection = serde_json::from_str(r#"{"restricted": true}"#).unwrap();
+        assert!(collection.is_locked());
+    }
+
+    #[test]
+    fn test_category_deserialization() {
+        let category: Category = serde_json::from_str(r#""leak""#).unwrap();
+        assert_eq!(category, Category::Leak);
+        let category: Category = serde_json::from_str(r#""something-new""#).unwrap();
+        assert_eq!(category, Category::Other);
+    }
+
     #[test]
     fn test_deserialization_no_collection() {
         let test: String = read_to_string("testdata/export.json").unwrap();
@@ -120,6 +672,63 @@ mod tests {
         assert!(status.results[0].collection.is_none());
     }
 
+    #[test]
+    fn test_progress() {
+        assert_eq!(progress(0, 0, 0), None);
+        assert_eq!(progress(1, 0, 0), Some(1.0));
+        assert_eq!(progress(1, 1, 2), Some(0.25));
+        assert_eq!(format_progress(progress(999, 0, 1)), "99%");
+        assert_eq!(format_progress(None), "idle");
+    }
+
+    #[test]
+    fn test_eta() {
+        let test = read_to_string("testdata/results.json").unwrap();
+        let mut status: Status = serde_json::from_str(&test).unwrap();
+        let result = &mut status.results[0];
+        assert_eq!(result.eta(), None);
+        result.remaining_time = Some(0.0);
+        assert_eq!(result.eta(), None);
+        result.remaining_time = Some(90.5);
+        assert_eq!(result.eta(), Some(TimeDelta::seconds(90)));
+        result.remaining_time = Some(1e300);
+        assert_eq!(result.eta(), None);
+        result.remaining_time = Some(f64::INFINITY);
+        assert_eq!(result.eta(), None);
+    }
+
+    #[test]
+    fn test_is_stalled() {
+        let test = read_to_string("testdata/results.json").unwrap();
+        let mut status: Status = serde_json::from_str(&test).unwrap();
+        let result = &mut status.results[0];
+        let last_update = result.last_update_time().unwrap();
+        let threshold = TimeDelta::minutes(10);
+
+        assert!(!result.is_stalled(last_update + TimeDelta::seconds(599), threshold));
+        assert!(result.is_stalled(last_update + TimeDelta::seconds(601), threshold));
+
+        result.running = 0;
+        assert!(!result.is_stalled(last_update + TimeDelta::hours(1), threshold));
+
+        result.running = 1;
+        result.last_update = None;
+        assert!(!result.is_stalled(last_update + TimeDelta::hours(1), threshold));
+    }
+
+    #[test]
+    fn test_totals() {
+        let test = read_to_string("testdata/results.json").unwrap();
+        let status: Status = serde_json::from_str(&test).unwrap();
+        let totals = Totals::of(&status.results);
+        assert_eq!(
+            totals.pending,
+            status.results.iter().map(|r| r.pending as u64).sum::<u64>()
+        );
+        assert_eq!(Totals::of(&[]), Totals::default());
+        assert_eq!(Totals::default().progress(), None);
+    }
+
     #[test]
     fn test_metadata_deserialization() {
         let test = read_to_string("testdata/metadata.json").unwrap();
@@ -0,0 +1,171 @@
+use std::{cmp::Ordering, fmt::Display, str::FromStr};
+
+use crate::models::StatusResult;
+
+/// Columns the collections table can be sorted by.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum SortColumn {
+    Pending,
+    Running,
+    Finished,
+    Failed,
+    Label,
+    LastUpdate,
+    Eta,
+}
+
+impl SortColumn {
+    pub const ALL: [SortColumn; 7] = [
+        SortColumn::Pending,
+        SortColumn::Running,
+        SortColumn::Finished,
+        SortColumn::Failed,
+        SortColumn::Label,
+        SortColumn::LastUpdate,
+        SortColumn::Eta,
+    ];
+
+    /// The column following this one when cycling with `s`, `None` after the last one.
+    pub fn next(self) -> Option<SortColumn> {
+        match self {
+            SortColumn::Pending => Some(SortColumn::Running),
+            SortColumn::Running => Some(SortColumn::Finished),
+            SortColumn::Finished => Some(SortColumn::Failed),
+            SortColumn::Failed => Some(SortColumn::Label),
+            SortColumn::Label => Some(SortColumn::LastUpdate),
+            SortColumn::LastUpdate => Some(SortColumn::Eta),
+            SortColumn::Eta => None,
+        }
+    }
+
+    fn default_descending(self) -> bool {
+        !matches!(self, SortColumn::Label | SortColumn::Eta)
+    }
+}
+
+impl Display for SortColumn {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            SortColumn::Pending => "pending",
+            SortColumn::Running => "running",
+            SortColumn::Finished => "finished",
+            SortColumn::Failed => "failed",
+            SortColumn::Label => "label",
+            SortColumn::LastUpdate => "last_update",
+            SortColumn::Eta => "eta",
+        };
+        f.write_str(name)
+    }
+}
+
+impl FromStr for SortColumn {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        SortColumn::ALL
+            .into_iter()
+            .find(|column| column.to_string() == s)
+            .ok_or_else(|| {
+                format!(
+                    "unknown sort column \"{}\", expected one of: {}",
+                    s,
+                    SortColumn::ALL.map(|column| column.to_string()).join(", ")
+                )
+            })
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Sort {
+    pub column: SortColumn,
+    pub descending: bool,
+}
+
+impl Sort {
+    pub fn new(column: SortColumn) -> Self {
+        Self {
+            column,
+            descending: column.default_descending(),
+        }
+    }
+
+    /// Orders two results by the sort column, breaking ties by collection id
+    /// so rows keep their relative position between fetches. Results without
+    /// an ETA always sort last when sorting by ETA.
+    pub fn compare(&self, a: &StatusResult, b: &StatusResult) -> Ordering {
+        if self.column == SortColumn::Eta {
+            match (a.eta(), b.eta()) {
+                (Some(_), None) => return Ordering::Less,
+                (None, Some(_)) => return Ordering::Greater,
+                (None, None) => return a.cmp_id(b),
+                (Some(_), Some(_)) => {}
+            }
+        }
+        let ordering = match self.column {
+            SortColumn::Pending => a.pending.cmp(&b.pending),
+            SortColumn::Running => a.running.cmp(&b.running),
+            SortColumn::Finished => a.finished.cmp(&b.finished),
+            SortColumn::Failed => a.failures().cmp(&b.failures()),
+            SortColumn::Label => a.label().to_lowercase().cmp(&b.label().to_lowercase()),
+            SortColumn::LastUpdate => a.last_update.cmp(&b.last_update),
+            SortColumn::Eta => a.eta().cmp(&b.eta()),
+        };
+        let ordering = match self.descending {
+            true => ordering.reverse(),
+            false => ordering,
+        };
+        ordering.then_with(|| a.cmp_id(b))
+    }
+}
+
+/// Parses `column` or `column:asc` / `column:desc`, as used in the config file.
+impl FromStr for Sort {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (column, direction) = match s.split_once(':') {
+            Some((column, direction)) => (column, Some(direction)),
+            None => (s, None),
+        };
+        let mut sort = Sort::new(column.parse()?);
+        match direction {
+            Some("asc") => sort.descending = false,
+            Some("desc") => sort.descending = true,
+            Some(direction) => {
+                return Err(format!(
+                    "unknown sort direction \"{}\", expected asc or desc",
+                    direction
+                ))
+            }
+            None => {}
+        }
+        Ok(sort)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_sort() {
+        assert_eq!(
+            "pending:desc".parse(),
+            Ok(Sort {
+                column: SortColumn::Pending,
+                descending: true
+            })
+        );
+        assert_eq!(
+            "last_update:asc".parse(),
+            Ok(Sort {
+                column: SortColumn::LastUpdate,
+                descending: false
+            })
+        );
+        assert_eq!("label".parse(), Ok(Sort::new(SortColumn::Label)));
+        assert!("pending:up".parse::<Sort>().is_err());
+        let error = "size".parse::<Sort>().unwrap_err();
+        assert!(error.contains("pending, running, finished"));
+    }
+}
@@ -0,0 +1,67 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+/// What is kept between sessions, in `$XDG_STATE_HOME/aleph-tui.json`
+/// (`$HOME/.local/state/aleph-tui.json` by default).
+#[derive(Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct State {
+    /// Keys of the pinned results, by profile name.
+    #[serde(default)]
+    pub pins: BTreeMap<String, BTreeSet<String>>,
+}
+
+impl State {
+    pub fn path() -> Option<PathBuf> {
+        let dir = match std::env::var_os("XDG_STATE_HOME").filter(|dir| !dir.is_empty()) {
+            Some(dir) => PathBuf::from(dir),
+            None => home::home_dir()?.join(".local/state"),
+        };
+        Some(dir.join("aleph-tui.json"))
+    }
+
+    /// Reads the state from `path`, starting over if it is missing or broken.
+    pub fn load(path: &Path) -> Self {
+        std::fs::read(path)
+            .ok()
+            .and_then(|json| serde_json::from_slice(&json).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, path: &Path) -> color_eyre::Result<()> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, serde_json::to_vec_pretty(self)?)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_load_save() {
+        let path = std::env::temp_dir()
+            .join(format!("aleph-tui-test-{}", std::process::id()))
+            .join("state.json");
+        assert_eq!(State::load(&path), State::default());
+
+        let mut state = State::default();
+        state
+            .pins
+            .entry("local".to_string())
+            .or_default()
+            .insert("7".to_string());
+        state.save(&path).unwrap();
+        assert_eq!(State::load(&path), state);
+
+        std::fs::write(&path, "{").unwrap();
+        assert_eq!(State::load(&path), State::default());
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+}
@@ -0,0 +1,163 @@
+use std::collections::HashMap;
+
+use chrono::TimeDelta;
+use ratatui::style::{Color, Modifier, Style, Stylize};
+
+use crate::models::Category;
+
+/// Named styles used by `ui::render`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Theme {
+    pub title: Style,
+    pub title_failures: Style,
+    pub title_stalled: Style,
+    pub title_frozen: Style,
+    pub tab_active: Style,
+    pub maintenance_banner: Style,
+    pub header: Style,
+    pub row: Style,
+    pub row_failed: Style,
+    pub row_stalled: Style,
+    pub row_backlog: Style,
+    pub totals: Style,
+    pub selection: Style,
+    /// Rows selected with `v`.
+    pub visual: Style,
+    /// Background of every other row, if enabled in the config.
+    pub stripe: Style,
+    /// Counts that changed with the last fetch, `None` to leave them as is.
+    pub changed: Option<Style>,
+    pub increase: Style,
+    pub decrease: Style,
+    pub chart_pending: Style,
+    pub chart_running: Style,
+    pub gauge_done: Style,
+    pub gauge_todo: Style,
+    pub muted: Style,
+    pub badge: Style,
+    pub filter_label: Style,
+    pub filter_match: Style,
+    pub error: Style,
+    pub toast: Style,
+    pub toast_warning: Style,
+    pub status_bar: Style,
+    pub popup_border: Style,
+    pub active_profile: Style,
+    pub json_key: Style,
+    pub json_string: Style,
+    pub json_number: Style,
+    /// `true`, `false` and `null` in JSON.
+    pub json_literal: Style,
+    /// Accent of the category marker in front of each row, categories
+    /// without an entry get no marker.
+    pub categories: HashMap<Category, Style>,
+    /// Styles of the last-update cell, by the largest age they apply to in
+    /// ascending order. Older updates get `last_update_oldest`.
+    pub last_update_ages: Vec<(TimeDelta, Style)>,
+    pub last_update_oldest: Style,
+}
+
+impl Theme {
+    /// Style of a last-update cell for an update `age` old.
+    pub fn last_update_age(&self, age: TimeDelta) -> Style {
+        self.last_update_ages
+            .iter()
+            .find(|(max_age, _)| age < *max_age)
+            .map(|(_, style)| *style)
+            .unwrap_or(self.last_update_oldest)
+    }
+
+    pub fn category(&self, category: Category) -> Option<Style> {
+        self.categories.get(&category).copied()
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            title: Style::new(),
+            title_failures: Style::new().red().bold(),
+            title_stalled: Style::new().light_magenta().bold(),
+            title_frozen: Style::new().light_cyan().bold(),
+            tab_active: Style::new().add_modifier(Modifier::REVERSED),
+            maintenance_banner: Style::new().black().on_yellow().bold(),
+            header: Style::new(),
+            row: Style::new(),
+            row_failed: Style::new().red(),
+            row_stalled: Style::new().light_magenta(),
+            row_backlog: Style::new().yellow(),
+            totals: Style::new().bold(),
+            selection: Style::new().add_modifier(Modifier::REVERSED),
+            stripe: Style::new().bg(Color::Indexed(236)),
+            visual: Style::new().bg(Color::Indexed(24)),
+            changed: Some(Style::new().bold().bg(Color::Indexed(238))),
+            increase: Style::new().yellow(),
+            decrease: Style::new().green(),
+            chart_pending: Style::new().yellow(),
+            chart_running: Style::new().cyan(),
+            gauge_done: Style::new().black().on_green(),
+            gauge_todo: Style::new().on_dark_gray(),
+            muted: Style::new().dim(),
+            badge: Style::new().black().on_gray(),
+            filter_label: Style::new().bold(),
+            filter_match: Style::new().bold().underlined(),
+            error: Style::new().red(),
+            toast: Style::new().black().on_green(),
+            toast_warning: Style::new().black().on_yellow(),
+            status_bar: Style::new(),
+            popup_border: Style::new(),
+            active_profile: Style::new().green().bold(),
+            json_key: Style::new().cyan(),
+            json_string: Style::new().green(),
+            json_number: Style::new().yellow(),
+            json_literal: Style::new().magenta(),
+            categories: [
+                (Category::Casefile, Color::Blue),
+                (Category::Leak, Color::Magenta),
+                (Category::News, Color::Green),
+                (Category::Company, Color::Cyan),
+                (Category::Land, Color::Cyan),
+                (Category::Gazette, Color::Cyan),
+                (Category::Court, Color::Cyan),
+                (Category::Regulatory, Color::Cyan),
+                (Category::Transport, Color::Cyan),
+                (Category::Customs, Color::Cyan),
+                (Category::Census, Color::Cyan),
+                (Category::License, Color::Cyan),
+                (Category::Sanctions, Color::LightMagenta),
+                (Category::Poi, Color::LightMagenta),
+                (Category::Procurement, Color::LightBlue),
+                (Category::Finance, Color::LightBlue),
+                (Category::Grey, Color::Gray),
+                (Category::Library, Color::Gray),
+            ]
+            .into_iter()
+            .map(|(category, color)| (category, Style::new().fg(color)))
+            .collect(),
+            last_update_ages: vec![
+                (TimeDelta::minutes(1), Style::new().green()),
+                (TimeDelta::minutes(10), Style::new()),
+                (TimeDelta::minutes(30), Style::new().yellow()),
+            ],
+            last_update_oldest: Style::new().red(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_last_update_age() {
+        let theme = Theme::default();
+        let style = |seconds| theme.last_update_age(TimeDelta::seconds(seconds));
+        assert_eq!(style(0), Style::new().green());
+        assert_eq!(style(59), Style::new().green());
+        assert_eq!(style(60), Style::new());
+        assert_eq!(style(10 * 60), Style::new().yellow());
+        assert_eq!(style(30 * 60 - 1), Style::new().yellow());
+        assert_eq!(style(30 * 60), Style::new().red());
+        assert_eq!(style(86_400), Style::new().red());
+    }
+}
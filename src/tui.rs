@@ -1,7 +1,8 @@
-use std::{io, panic};
+use std::{io, panic, time::Instant};
 
 use color_eyre::Result;
 use crossterm::{
+    cursor,
     event::{DisableMouseCapture, EnableMouseCapture},
     terminal::{self, EnterAlternateScreen, LeaveAlternateScreen},
 };
@@ -19,12 +20,49 @@ pub struct Tui {
     terminal: CrosstermTerminal,
     /// Terminal event handler.
     pub events: EventHandler,
+    /// Whether the terminal is set up and needs to be restored.
+    entered: bool,
+}
+
+/// Restores the terminal before the panic message is printed, so that a
+/// panic doesn't leave it in raw mode on the alternate screen.
+///
+/// Install it after any other hook, which then runs with the terminal back.
+pub fn install_panic_hook() {
+    chain_panic_hook(|| {
+        // Nothing more can be done if this fails while panicking.
+        let _ = restore();
+    });
+}
+
+fn chain_panic_hook(restore: fn()) {
+    let panic_hook = panic::take_hook();
+    panic::set_hook(Box::new(move |panic| {
+        restore();
+        panic_hook(panic);
+    }));
+}
+
+/// Leaves raw mode and the alternate screen and shows the cursor again.
+pub fn restore() -> Result<()> {
+    terminal::disable_raw_mode()?;
+    crossterm::execute!(
+        io::stderr(),
+        LeaveAlternateScreen,
+        DisableMouseCapture,
+        cursor::Show
+    )?;
+    Ok(())
 }
 
 impl Tui {
     /// Constructs a new instance of [`Tui`].
     pub fn new(terminal: CrosstermTerminal, events: EventHandler) -> Self {
-        Self { terminal, events }
+        Self {
+            terminal,
+            events,
+            entered: false,
+        }
     }
 
     /// Initializes the terminal interface.
@@ -33,15 +71,7 @@ impl Tui {
     pub fn enter(&mut self) -> Result<()> {
         terminal::enable_raw_mode()?;
         crossterm::execute!(io::stderr(), EnterAlternateScreen, EnableMouseCapture)?;
-
-        // Define a custom panic hook to reset the terminal properties.
-        // This way, you won't have your terminal messed up if an unexpected error happens.
-        let panic_hook = panic::take_hook();
-        panic::set_hook(Box::new(move |panic| {
-            Self::reset().expect("failed to reset the terminal");
-            panic_hook(panic);
-        }));
-
+        self.entered = true;
         self.terminal.hide_cursor()?;
         self.terminal.clear()?;
         Ok(())
@@ -52,17 +82,20 @@ impl Tui {
     /// [`Draw`]: tui::Terminal::draw
     /// [`rendering`]: crate::ui:render
     pub fn draw(&mut self, app: &mut App) -> Result<()> {
-        self.terminal.draw(|frame| ui::render(app, frame))?;
-        Ok(())
-    }
-
-    /// Resets the terminal interface.
-    ///
-    /// This function is also used for the panic hook to revert
-    /// the terminal properties if unexpected errors occur.
-    fn reset() -> Result<()> {
-        terminal::disable_raw_mode()?;
-        crossterm::execute!(io::stderr(), LeaveAlternateScreen, DisableMouseCapture)?;
+        if app.redraw_requested {
+            self.terminal.clear()?;
+            app.redraw_requested = false;
+        }
+        let started = Instant::now();
+        let mut render_duration = None;
+        self.terminal.draw(|frame| {
+            ui::render(app, frame);
+            render_duration = Some(started.elapsed());
+        })?;
+        app.metrics.render_duration = render_duration;
+        app.dirty = false;
+        app.drawn_at = Instant::now();
+        app.metrics.draw_duration = Some(started.elapsed());
         Ok(())
     }
 
@@ -70,8 +103,37 @@ impl Tui {
     ///
     /// It disables the raw mode and reverts back the terminal properties.
     pub fn exit(&mut self) -> Result<()> {
-        Self::reset()?;
-        self.terminal.show_cursor()?;
-        Ok(())
+        self.entered = false;
+        restore()
+    }
+}
+
+impl Drop for Tui {
+    /// Restores the terminal if [`Tui::exit`] wasn't reached, e.g. when an
+    /// error is returned from the main loop.
+    fn drop(&mut self) {
+        if self.entered {
+            let _ = self.exit();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    use super::*;
+
+    static RESTORED: AtomicBool = AtomicBool::new(false);
+
+    #[test]
+    fn test_panic_hook_restores() {
+        let original = panic::take_hook();
+        panic::set_hook(Box::new(|_| {}));
+        chain_panic_hook(|| RESTORED.store(true, Ordering::SeqCst));
+        let result = panic::catch_unwind(|| panic!("while drawing"));
+        panic::set_hook(original);
+        assert!(result.is_err());
+        assert!(RESTORED.load(Ordering::SeqCst));
     }
 }
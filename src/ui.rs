@@ -1,4 +1,6 @@
-use chrono::{Local, NaiveDateTime, Utc};
+use std::{borrow::Cow, cmp::Ordering, ops::Range, rc::Rc, time::Duration};
+
+use chrono::{DateTime, Local, NaiveDateTime, TimeDelta, Utc};
 use humanize_duration::prelude::DurationExt;
 use humanize_duration::Truncate;
 use itertools::Itertools;
@@ -6,12 +8,34 @@ use num_format::{Locale, ToFormattedString};
 use ratatui::{
     layout::{Alignment, Constraint, Direction, Layout, Rect},
     prelude::Frame,
-    style::{Modifier, Style, Stylize},
-    text::Line,
-    widgets::{Block, Borders, Padding, Paragraph, Row, Table},
+    style::Style,
+    text::{Line, Span, Text},
+    widgets::{
+        Axis, Block, Cell, Chart, Clear, Dataset, GraphType, HighlightSpacing, Padding, Paragraph,
+        Row, ScrollbarState, Table, TableState, Tabs, Wrap,
+    },
 };
 
-use crate::{app::App, models::StageOrStages};
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+pub mod input;
+
+use crate::{
+    action::Action,
+    app::{
+        App, CurrentView, Focus, Follow, InputMode, ProfileHealth, Severity, Tab, TimestampMode,
+    },
+    glyphs::Glyphs,
+    history::sparkline,
+    inspector::{self, JsonToken},
+    models::{
+        format_progress, seconds_delta, Collection, Stage, StageOrStages, StatusResult, Totals,
+    },
+    sort::SortColumn,
+    theme::Theme,
+    update,
+};
 
 /// helper function to create a centered rect using up certain percentage of the available rect `r`
 fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
@@ -36,192 +60,2949 @@ fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
         .split(popup_layout[1])[1] // Return the middle chunk
 }
 
-pub fn render(app: &mut App, f: &mut Frame) {
-    let chunks = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints([
-            Constraint::Length(4),
-            Constraint::Min(1),
-            Constraint::Length(9),
-            Constraint::Length(1),
+/// Renders the filter query line above the collections table, with the query
+/// being edited in front while typing.
+fn render_filter(app: &App, f: &mut Frame, area: Rect) {
+    let counts = format!(" ({} of {})", app.rows.len(), app.status.results.len());
+    let mut line_area = area;
+    let mut spans = match app.input_mode {
+        InputMode::Filter => {
+            // The query gets the room left next to the counts, plus one
+            // column for the cursor, which the counts start with otherwise.
+            let counts = counts.trim_start();
+            let width = (app.filter.width() + 1)
+                .min((area.width as usize).saturating_sub(1 + counts.width()))
+                .max(1) as u16;
+            let [prompt_area, input_area, rest] = Layout::horizontal([
+                Constraint::Length(1),
+                Constraint::Length(width),
+                Constraint::Fill(1),
+            ])
+            .areas(area);
+            f.render_widget(Span::raw("/"), prompt_area);
+            input::render(&app.filter, f, input_area);
+            line_area = rest;
+            vec![Span::styled(counts.to_string(), app.theme.muted)]
+        }
+        InputMode::Normal if app.filter.is_empty() => {
+            vec![Span::styled(
+                counts.trim_start().to_string(),
+                app.theme.muted,
+            )]
+        }
+        InputMode::Normal => vec![
+            Span::styled("Filter: ", app.theme.filter_label),
+            Span::raw(app.filter.value().to_string()),
+            Span::styled(counts, app.theme.muted),
+        ],
+    };
+    if let Some(category) = app.category_filter {
+        spans.extend([
+            Span::styled("  Category: ", app.theme.filter_label),
+            Span::raw(category.to_string()),
+        ]);
+    }
+    if app.failures_only {
+        spans.push(Span::styled("  failures only", app.theme.filter_label));
+    }
+    if app.hide_idle {
+        spans.push(Span::styled(
+            format!("  {} idle hidden", app.idle_hidden),
+            app.theme.muted,
+        ));
+    }
+    f.render_widget(Paragraph::new(Line::from(spans)), line_area);
+}
+
+/// Severity-based style for a collections table row: red when tasks failed,
+/// yellow when the backlog exceeds `pending_threshold`.
+fn row_style(result: &StatusResult, stalled: bool, pending_threshold: u32, theme: &Theme) -> Style {
+    if result.failures() > 0 {
+        theme.row_failed
+    } else if stalled {
+        theme.row_stalled
+    } else if result.pending > pending_threshold {
+        theme.row_backlog
+    } else {
+        theme.row
+    }
+}
+
+/// Background of every other row when `stripes` are on. Stripes go by the
+/// position in the whole table rather than on screen, so they move along
+/// when scrolling.
+fn stripe_style(stripes: bool, index: usize, theme: &Theme) -> Style {
+    match stripes && index % 2 == 1 {
+        true => theme.stripe,
+        false => Style::new(),
+    }
+}
+
+/// Truncates `label` to `width` columns like [`truncate`], styling the parts
+/// matching the lowercase `query` with `style`.
+fn highlight_matches(
+    label: &str,
+    query: &str,
+    width: usize,
+    style: Style,
+    ellipsis: &str,
+) -> Line<'static> {
+    let truncated = truncate(label, width, ellipsis);
+    if query.is_empty() {
+        return Line::from(truncated.into_owned());
+    }
+    // Lowercasing can change the length of a character, so match on the
+    // lowercase text and map the matches back to the original characters.
+    let mut lowercase = String::new();
+    let mut char_ranges = Vec::new();
+    for c in label.chars() {
+        let start = lowercase.len();
+        lowercase.extend(c.to_lowercase());
+        char_ranges.push(start..lowercase.len());
+    }
+    let matches: Vec<_> = lowercase
+        .match_indices(query)
+        .map(|(start, m)| start..start + m.len())
+        .collect();
+    let is_match = |i: usize| {
+        char_ranges.get(i).is_some_and(|range| {
+            matches
+                .iter()
+                .any(|m| range.start < m.end && m.start < range.end)
+        })
+    };
+
+    let mut spans: Vec<Span> = Vec::new();
+    let mut current = String::new();
+    let mut current_match = false;
+    let visible = match truncated == label {
+        true => usize::MAX,
+        false => truncated.chars().count() - fitting_ellipsis(ellipsis, width).chars().count(),
+    };
+    for (i, c) in truncated.chars().enumerate() {
+        let matched = i < visible && is_match(i);
+        if matched != current_match && !current.is_empty() {
+            let text = std::mem::take(&mut current);
+            spans.push(match current_match {
+                true => Span::styled(text, style),
+                false => Span::raw(text),
+            });
+        }
+        current_match = matched;
+        current.push(c);
+    }
+    if !current.is_empty() {
+        spans.push(match current_match {
+            true => Span::styled(current, style),
+            false => Span::raw(current),
+        });
+    }
+    Line::from(spans)
+}
+
+/// Renders an error in full, word-wrapped and scrollable.
+fn render_error_popup(app: &mut App, f: &mut Frame) {
+    let Some(error) = &app.shown_error else {
+        return;
+    };
+    let profile = app
+        .config
+        .profiles
+        .get(error.profile)
+        .map(|profile| profile.name.as_str())
+        .unwrap_or_default();
+    let area = centered_rect(70, 60, f.area());
+    let block = app
+        .glyphs
+        .block()
+        .title("Error")
+        .border_style(app.theme.popup_border)
+        .padding(Padding::horizontal(1));
+    let inner = block.inner(area);
+    let text = vec![
+        Line::from(vec![
+            Span::styled("Time: ", app.theme.filter_label),
+            Span::raw(error.time.format("%Y-%m-%d %H:%M:%S").to_string()),
+        ]),
+        Line::from(vec![
+            Span::styled("URL:  ", app.theme.filter_label),
+            Span::raw(error.url.to_string()),
+        ]),
+        Line::from(vec![
+            Span::styled("Kind: ", app.theme.filter_label),
+            Span::raw(error.kind.to_string()),
+            Span::styled("  Profile: ", app.theme.filter_label),
+            Span::raw(profile.to_string()),
+        ]),
+        Line::default(),
+        Line::styled(error.message.to_string(), app.theme.error),
+    ];
+    let width = inner.width.max(1) as usize;
+    let height: usize = text.iter().map(|l| l.width().max(1).div_ceil(width)).sum();
+    let max_scroll = height.saturating_sub(inner.height as usize) as u16;
+    app.error_scroll = app.error_scroll.min(max_scroll);
+
+    f.render_widget(Clear, area);
+    f.render_widget(
+        Paragraph::new(text)
+            .wrap(Wrap { trim: false })
+            .scroll((app.error_scroll, 0))
+            .block(block),
+        area,
+    );
+}
+
+/// Renders the JSON of a result, tinted, with the search line at the bottom.
+fn render_inspector(app: &mut App, f: &mut Frame) {
+    let Some(inspector) = app.inspector.as_mut() else {
+        return;
+    };
+    let area = centered_rect(80, 80, f.area());
+    let block = app
+        .glyphs
+        .block()
+        .title(format!("JSON: {}", inspector.title))
+        .border_style(app.theme.popup_border)
+        .padding(Padding::horizontal(1));
+    let inner = block.inner(area);
+    f.render_widget(Clear, area);
+    f.render_widget(block, area);
+    let show_search = inspector.searching || !inspector.search.is_empty();
+    let [text_area, search_area] =
+        Layout::vertical([Constraint::Min(0), Constraint::Length(show_search as u16)]).areas(inner);
+    inspector.visible_lines = text_area.height as usize;
+    inspector.scroll_by(0);
+
+    let query = inspector.query();
+    let text: Vec<Line> = inspector
+        .lines
+        .iter()
+        .skip(inspector.scroll)
+        .take(inspector.visible_lines)
+        .map(|line| json_line(line, &query, &app.theme))
+        .collect();
+    f.render_widget(Paragraph::new(text), text_area);
+
+    if show_search {
+        let matches = inspector.matches().len();
+        let count = format!(" {} matches", matches);
+        let [prompt, input, rest] = Layout::horizontal([
             Constraint::Length(1),
+            Constraint::Length(inspector.search.width() as u16 + 1),
+            Constraint::Min(0),
         ])
-        .split(f.area());
-    let title_block = Block::default()
-        .borders(Borders::ALL)
-        .border_type(ratatui::widgets::BorderType::Rounded);
+        .areas(search_area);
+        f.render_widget(Span::styled("/", app.theme.filter_label), prompt);
+        match inspector.searching {
+            true => input::render(&inspector.search, f, input),
+            false => f.render_widget(Span::raw(inspector.search.value().to_string()), input),
+        }
+        f.render_widget(Span::styled(count, app.theme.muted), rest);
+    }
+}
 
-    let text = vec![
-        Line::from(match &app.metadata.app.title {
-            Some(title) => format!(
-                "{} ({}): {} jobs running",
-                title,
-                app.current_profile().name,
-                app.status.total
-            ),
-            None => format!(
-                "({}): {} jobs running",
-                app.current_profile().name,
-                app.status.total
-            ),
-        }),
-        Line::from(
-            match (&app.metadata.app.version, &app.metadata.app.ftm_version) {
-                (Some(aleph), Some(ftm)) => format!("version: {}, followthemoney: {}", aleph, ftm),
-                (None, Some(ftm)) => format!("followthemoney: {}", ftm),
-                (Some(aleph), None) => format!("version: {}", aleph),
-                (None, None) => String::default(),
-            },
+/// A line of pretty-printed JSON, tinted by token, with the parts matching the
+/// lowercase `query` styled as filter matches.
+fn json_line(line: &str, query: &str, theme: &Theme) -> Line<'static> {
+    let mut styles = vec![Style::new(); line.len()];
+    for (token, range) in inspector::tokenize(line) {
+        let style = match token {
+            JsonToken::Key => theme.json_key,
+            JsonToken::String => theme.json_string,
+            JsonToken::Number => theme.json_number,
+            JsonToken::Literal => theme.json_literal,
+            JsonToken::Punctuation => Style::new(),
+        };
+        styles[range].fill(style);
+    }
+    if !query.is_empty() {
+        // Match on the lowercase text, character by character, as lowercasing
+        // can change the length of a character.
+        let chars: Vec<(usize, String)> = line
+            .char_indices()
+            .map(|(i, c)| (i, c.to_lowercase().collect()))
+            .collect();
+        let query: Vec<char> = query.chars().collect();
+        for start in 0..chars.len() {
+            let mut lowercase = chars[start..].iter().flat_map(|(_, c)| c.chars());
+            if query.iter().all(|q| lowercase.next() == Some(*q)) {
+                let mut length = 0;
+                let mut end = start;
+                while length < query.len() && end < chars.len() {
+                    length += chars[end].1.chars().count();
+                    end += 1;
+                }
+                let end = chars.get(end).map_or(line.len(), |(i, _)| *i);
+                for style in &mut styles[chars[start].0..end] {
+                    *style = style.patch(theme.filter_match);
+                }
+            }
+        }
+    }
+    let mut spans = Vec::new();
+    let mut start = 0;
+    for (i, _) in line.char_indices().skip(1) {
+        if styles[i] != styles[start] {
+            spans.push(Span::styled(line[start..i].to_string(), styles[start]));
+            start = i;
+        }
+    }
+    if start < line.len() {
+        spans.push(Span::styled(line[start..].to_string(), styles[start]));
+    }
+    Line::from(spans)
+}
+
+/// Most lines the error area below the table grows to.
+const ERROR_LINES: usize = 3;
+
+/// Cuts `text` into lines of at most `width` columns, breaking between
+/// grapheme clusters. Returns whether there were more than `max_lines` lines.
+fn hard_wrap(text: &str, width: usize, max_lines: usize) -> (Vec<String>, bool) {
+    let mut lines = vec![String::new()];
+    for grapheme in text.graphemes(true) {
+        let line = lines.last_mut().expect("there is always a line");
+        if line.width() + grapheme.width() > width && !line.is_empty() {
+            if lines.len() == max_lines {
+                return (lines, true);
+            }
+            lines.push(grapheme.to_string());
+        } else {
+            line.push_str(grapheme);
+        }
+    }
+    (lines, false)
+}
+
+/// Wraps the current error message to `width` columns, pointing at the
+/// full-error popup when it doesn't fit into the error area.
+fn error_lines(app: &App, width: usize) -> Vec<Line<'static>> {
+    if app.error_message.is_empty() {
+        return Vec::new();
+    }
+    let has_details = app.last_error.is_some();
+    let (mut lines, truncated) = hard_wrap(&app.error_message, width.max(1), ERROR_LINES);
+    let hint = match (truncated, has_details) {
+        (true, true) => " [more: `e`]",
+        (true, false) => " [more]",
+        (false, true) => " (`e` for details)",
+        (false, false) => "",
+    };
+    let last = lines.pop().unwrap_or_default();
+    let last = match truncated {
+        true => truncate(
+            &last,
+            width.saturating_sub(hint.width()),
+            app.glyphs.ellipsis,
+        )
+        .into_owned(),
+        false => last,
+    };
+    let mut lines: Vec<Line> = lines.into_iter().map(Line::from).collect();
+    match !truncated && last.width() + hint.width() > width {
+        // Leave out the hint rather than wrapping it onto a line of its own.
+        true => lines.push(Line::from(last)),
+        false => lines.push(Line::from(vec![
+            Span::raw(last),
+            Span::styled(hint, app.theme.muted),
+        ])),
+    }
+    lines
+}
+
+/// Describes when the next fetch happens, for the status bar.
+fn fetch_status(
+    is_fetching: bool,
+    paused: bool,
+    interval: Duration,
+    next_fetch_in: Duration,
+    glyphs: &Glyphs,
+) -> String {
+    if is_fetching {
+        return glyphs.fetching.to_string();
+    }
+    if paused {
+        return format!("{} {} press space to resume", glyphs.paused, glyphs.dash);
+    }
+    match next_fetch_in.as_secs_f64().ceil() as u64 {
+        0 => format!("fetching every {}s - next fetch now", interval.as_secs()),
+        seconds => format!(
+            "fetching every {}s - next fetch in {}s",
+            interval.as_secs(),
+            seconds
         ),
-    ];
-    let title = Paragraph::new(text).block(title_block);
-    f.render_widget(title, chunks[0]);
+    }
+}
 
-    let mut rows = Vec::new();
-    let now = Utc::now().naive_utc();
-    for result in &app.status.results {
-        let last_update = match result.last_update.clone() {
-            Some(t) => {
-                let last_update = NaiveDateTime::parse_from_str(&t, "%Y-%m-%dT%H:%M:%S.%f")
-                    .expect("Failed to parse last_update timestamp");
-                let last_update = now - last_update;
-                let last_update = last_update.human(Truncate::Second);
-                last_update.to_string()
+/// Time passed between `time` and `now`, never negative even if the clock was
+/// set back in between.
+fn elapsed_since(time: DateTime<Local>, now: DateTime<Local>) -> TimeDelta {
+    (now - time).max(TimeDelta::zero())
+}
+
+/// Formats a server timestamp (naive UTC) as "time ago" or as an absolute
+/// date, depending on the active timestamp mode.
+fn format_timestamp(app: &App, timestamp: NaiveDateTime, now: NaiveDateTime) -> String {
+    match app.timestamp_mode {
+        TimestampMode::Relative => (now - timestamp)
+            .max(TimeDelta::zero())
+            .human(Truncate::Second)
+            .to_string(),
+        TimestampMode::Absolute => {
+            let timestamp = timestamp.and_utc();
+            match app.config.local_time {
+                true => timestamp
+                    .with_timezone(&Local)
+                    .format(&app.config.date_format)
+                    .to_string(),
+                false => timestamp.format(&app.config.date_format).to_string(),
             }
-            None => "".to_string(),
-        };
+        }
+    }
+}
 
-        let collection_id = match &result.collection {
-            Some(c) => c.id.to_string(),
-            None => "-".to_string(),
+/// Formats the estimated time until a job is done, either as a duration or as
+/// the expected completion time. Blank when there is no estimate.
+fn format_eta(app: &App, result: &StatusResult, now: NaiveDateTime) -> String {
+    match (result.eta(), app.timestamp_mode) {
+        (Some(eta), TimestampMode::Relative) => eta.human(Truncate::Second).to_string(),
+        (Some(eta), TimestampMode::Absolute) => now
+            .checked_add_signed(eta)
+            .map(|eta| format_timestamp(app, eta, now))
+            .unwrap_or_default(),
+        (None, _) => String::default(),
+    }
+}
+
+/// Renders the profile switcher popup, widening it to fit the longest URL.
+fn render_profile_selector(app: &mut App, f: &mut Frame) {
+    let mut rows = Vec::new();
+    let mut name_width = 0;
+    let mut origin_width = 0;
+    for (idx, profile) in app.config.profiles.iter().enumerate() {
+        let ordinal = format!("{}", idx + 1);
+        let default_marker = match profile.name == app.config.default_profile() {
+            true => "*",
+            false => "",
         };
-        let collection_label = match &result.collection {
-            Some(c) => c.label.to_string(),
-            None => match result.stages.clone() {
-                Some(s) => match s {
-                    StageOrStages::Stage(s) => s.stage,
-                    StageOrStages::Stages(v) => {
-                        v.iter().map(|s| s.stage.to_string() + ", ").collect()
-                    }
-                },
-                None => "".to_string(),
-            },
+        let origin = profile.origin();
+        name_width = name_width.max(profile.name.width());
+        origin_width = origin_width.max(origin.width());
+        let style = match app.current_profile == profile.index {
+            true => app.theme.active_profile,
+            false => Style::new(),
         };
-        rows.push(Row::new(vec![
-            collection_id,
-            collection_label,
-            result.finished.to_formatted_string(&Locale::en),
-            result.running.to_formatted_string(&Locale::en),
-            result.pending.to_formatted_string(&Locale::en),
-            last_update,
-        ]))
+        rows.push(
+            Row::new([
+                ordinal,
+                default_marker.to_string(),
+                profile.name.clone(),
+                origin,
+            ])
+            .style(style),
+        );
     }
+
+    // ordinal, marker, borders, highlight symbol and column spacing
+    let width = (name_width + origin_width + 3 + 1 + 2 + HIGHLIGHT_SYMBOL.width() + 3) as u16;
+    let area_width = f.area().width.max(1);
+    let percent_x = (width.saturating_mul(100).div_ceil(area_width)).clamp(40, 90);
+    let area = centered_rect(percent_x, 25, f.area());
+
+    let popup_block = app
+        .glyphs
+        .block()
+        .title("Select profile")
+        .border_style(app.theme.popup_border);
+    f.render_widget(Clear, area);
+    f.render_widget(popup_block.clone(), area);
+
     let widths = [
-        Constraint::Length(5),
-        Constraint::Min(20),
-        Constraint::Length(10),
-        Constraint::Length(10),
-        Constraint::Length(10),
-        Constraint::Length(25),
+        Constraint::Length(3),
+        Constraint::Length(1),
+        Constraint::Length(name_width as u16),
+        Constraint::Fill(1),
     ];
-    let table = Table::new(rows, widths)
-        .header(
-            Row::new(vec![
-                "ID",
-                "Label",
-                "Finished",
-                "Running",
-                "Pending",
-                "Last update",
-            ])
-            .bottom_margin(1),
-        )
-        .highlight_style(Style::new().add_modifier(Modifier::REVERSED))
-        .highlight_symbol(">>");
-
-    f.render_stateful_widget(table, chunks[1], &mut app.collection_tablestate);
-
-    if let Some(index) = app.collection_tablestate.selected() {
-        let result = &app.status.results[index];
-        if let Some(stages) = &result.stages {
-            let body = match stages {
-                StageOrStages::Stage(stage) => stage.to_string(),
-                StageOrStages::Stages(stages) => {
-                    stages.iter().sorted_by_key(|s| &s.stage).join("\n")
-                }
-            };
-            let title = match &result.collection {
-                Some(col) => format!("Collection {} <{}>", col.collection_id, col.label),
-                None => "Details".to_string(),
-            };
-            let info_block = Block::default()
-                .title(title)
-                .padding(Padding::new(1, 1, 1, 1))
-                .borders(Borders::ALL)
-                .border_type(ratatui::widgets::BorderType::Rounded);
-            let info_block = Paragraph::new(body).block(info_block);
-            f.render_widget(info_block, chunks[2]);
-        };
+    let profile_table = Table::new(rows, widths)
+        .highlight_style(app.theme.selection)
+        .highlight_symbol(HIGHLIGHT_SYMBOL);
+    app.profile_area = popup_block.inner(area);
+    f.render_stateful_widget(profile_table, app.profile_area, &mut app.profile_tablestate);
+}
+
+/// Renders the command palette: the query with a cursor above the matching
+/// actions and their keys.
+fn render_command_palette(app: &mut App, f: &mut Frame) {
+    let area = centered_rect(50, 60, f.area());
+    let block = app
+        .glyphs
+        .block()
+        .title("Commands")
+        .border_style(app.theme.popup_border);
+    let inner = block.inner(area);
+    f.render_widget(Clear, area);
+    f.render_widget(block, area);
+    let [query_area, list_area] =
+        Layout::vertical([Constraint::Length(1), Constraint::Fill(1)]).areas(inner);
+
+    let [prompt_area, input_area] =
+        Layout::horizontal([Constraint::Length(2), Constraint::Fill(1)]).areas(query_area);
+    f.render_widget(Span::styled("> ", app.theme.filter_label), prompt_area);
+    input::render(&app.palette_query, f, input_area);
+
+    let actions = app.palette_actions();
+    if actions.is_empty() {
+        f.render_widget(Line::from("No matching commands"), list_area);
+        return;
     }
+    let key_width = actions
+        .iter()
+        .map(|action| action.key_label().width())
+        .max()
+        .unwrap_or_default() as u16;
+    let rows = actions
+        .into_iter()
+        .map(|action| Row::new([action.to_string(), action.key_label()]));
+    let table = Table::new(rows, [Constraint::Fill(1), Constraint::Length(key_width)])
+        .highlight_style(app.theme.selection)
+        .highlight_symbol(HIGHLIGHT_SYMBOL);
+    f.render_stateful_widget(table, list_area, &mut app.palette_tablestate);
+}
 
+/// Renders the dialog asking to confirm `action`, the default answer is no.
+fn render_confirm(app: &App, f: &mut Frame, action: Action) {
+    let area = centered_rect(40, 25, f.area());
+    let block = app
+        .glyphs
+        .block()
+        .title("Confirm")
+        .border_style(app.theme.popup_border)
+        .padding(Padding::horizontal(1));
+    let text = vec![
+        Line::from(action.confirmation().unwrap_or_default()),
+        Line::default(),
+        Line::from(vec![Span::styled("[y/N]", app.theme.filter_label)]),
+    ];
+    f.render_widget(Clear, area);
     f.render_widget(
-        Paragraph::new(app.error_message.to_string()).style(Style::new().red()),
-        chunks[3],
+        Paragraph::new(text)
+            .alignment(Alignment::Center)
+            .wrap(Wrap { trim: true })
+            .block(block),
+        area,
     );
+}
 
-    let status_bar_chunks = Layout::default()
-        .direction(Direction::Horizontal)
-        .constraints([Constraint::Min(1), Constraint::Min(1), Constraint::Min(25)])
-        .split(chunks[4]);
-    f.render_widget(
-        Block::default().title(format!("aleph-tui version {}", app.version)),
-        status_bar_chunks[0],
-    );
-    let fetching_icon = match app.is_fetching {
-        true => "🔄",
+/// Symbol in front of the selected row of a table.
+const HIGHLIGHT_SYMBOL: &str = ">>";
+
+/// Widest a numeric column of the collections table gets.
+const NUMBER_MAX_WIDTH: u16 = 14;
+
+/// Widest the ID column of the collections table gets.
+const ID_MAX_WIDTH: u16 = 12;
+
+/// Narrowest the label column of the collections table gets.
+const LABEL_MIN_WIDTH: u16 = 20;
+
+/// Width of a column fitting `header` and all `cells`, but at most `max`.
+/// Sortable columns leave room for the sort indicator next to the header.
+fn fit_column(header: &str, sortable: bool, cells: impl Iterator<Item = usize>, max: u16) -> u16 {
+    let header = header.width() + if sortable { 2 } else { 0 };
+    let widest = cells.fold(header, usize::max);
+    (widest as u16).min(max)
+}
+
+/// Width of `n` with thousands separators, without formatting it.
+fn number_width(n: u64) -> usize {
+    let digits = n.checked_ilog10().unwrap_or(0) as usize + 1;
+    digits + (digits - 1) / 3
+}
+
+/// Column widths of the collections table, fitted to the `results` on display.
+/// The label column gets the space left over.
+fn column_widths(
+    results: &[&StatusResult],
+    narrow: bool,
+    eta_width: Option<u16>,
+    timestamp_width: u16,
+    glyphs: &Glyphs,
+) -> Vec<Constraint> {
+    // The totals row below the table shares these widths.
+    let totals = Totals::of(results.iter().copied());
+    let number = |header: &str, value: fn(&StatusResult) -> u32, total: u64| {
+        let cells = results
+            .iter()
+            .map(|r| number_width(value(r) as u64))
+            .chain([number_width(total)]);
+        Constraint::Length(fit_column(header, true, cells, NUMBER_MAX_WIDTH))
+    };
+    let ids = results.iter().map(|r| match &r.collection {
+        Some(c) => c.id.width(),
+        None => 1,
+    });
+    let mut widths = vec![Constraint::Length(glyphs.category.width() as u16)];
+    // The lock column is the first to go on narrow terminals.
+    if !narrow {
+        widths.push(Constraint::Length(glyphs.lock.width() as u16));
+    }
+    widths.extend([
+        Constraint::Length(fit_column("ID", false, ids, ID_MAX_WIDTH)),
+        Constraint::Min(LABEL_MIN_WIDTH),
+        number("Finished", |r| r.finished, totals.finished),
+        number("Running", |r| r.running, totals.running),
+        number("Pending", |r| r.pending, totals.pending),
+        number("Failed", StatusResult::failures, totals.failed),
+        Constraint::Length(match narrow {
+            true => 5,
+            false => GAUGE_WIDTH,
+        }),
+    ]);
+    if let Some(eta_width) = eta_width {
+        widths.push(Constraint::Length(eta_width));
+    }
+    widths.extend([
+        Constraint::Length(SPARKLINE_WIDTH),
+        Constraint::Length(timestamp_width),
+    ]);
+    widths
+}
+
+/// Width the label column gets when the collections table is `width` columns wide.
+fn label_width(widths: &[Constraint], width: u16) -> u16 {
+    // The label is the only column that grows.
+    let label = widths
+        .iter()
+        .position(|width| matches!(width, Constraint::Min(_)))
+        .unwrap_or_default();
+    column_areas(widths, Rect::new(0, 0, width, 1), true)[label].width
+}
+
+/// Screen areas of the table columns laid out in `area`, the same way the
+/// table does it. `highlight` tells whether room is taken up by the
+/// highlight symbol, as it is while a row is selected.
+fn column_areas(widths: &[Constraint], area: Rect, highlight: bool) -> Rc<[Rect]> {
+    let [_, columns] = Layout::horizontal([
+        Constraint::Length(match highlight {
+            true => HIGHLIGHT_SYMBOL.width() as u16,
+            false => 0,
+        }),
+        Constraint::Fill(0),
+    ])
+    .areas(area);
+    Layout::horizontal(widths.iter().copied())
+        .spacing(1)
+        .split(columns)
+}
+
+/// Shortens `text` to at most `width` terminal columns, cutting between
+/// grapheme clusters and marking the cut with `ellipsis`, unless that
+/// doesn't fit either.
+pub(crate) fn truncate<'a>(text: &'a str, width: usize, ellipsis: &str) -> Cow<'a, str> {
+    if text.width() <= width {
+        return Cow::Borrowed(text);
+    }
+    let ellipsis = fitting_ellipsis(ellipsis, width);
+    let mut truncated = String::new();
+    let mut used = 0;
+    for grapheme in text.graphemes(true) {
+        used += grapheme.width();
+        if used + ellipsis.width() > width {
+            break;
+        }
+        truncated.push_str(grapheme);
+    }
+    truncated.push_str(ellipsis);
+    Cow::Owned(truncated)
+}
+
+/// Like [`truncate`], but cuts from the start, keeping the end of `text`.
+fn truncate_start<'a>(text: &'a str, width: usize, ellipsis: &str) -> Cow<'a, str> {
+    if text.width() <= width {
+        return Cow::Borrowed(text);
+    }
+    let ellipsis = fitting_ellipsis(ellipsis, width);
+    let mut kept = Vec::new();
+    let mut used = 0;
+    for grapheme in text.graphemes(true).rev() {
+        used += grapheme.width();
+        if used + ellipsis.width() > width {
+            break;
+        }
+        kept.push(grapheme);
+    }
+    let mut truncated = ellipsis.to_string();
+    truncated.extend(kept.into_iter().rev());
+    Cow::Owned(truncated)
+}
+
+fn fitting_ellipsis(ellipsis: &str, width: usize) -> &str {
+    match ellipsis.width() <= width {
+        true => ellipsis,
         false => "",
+    }
+}
+
+/// Terminals at least this wide show the details pane next to the table.
+const SPLIT_WIDTH: u16 = 220;
+
+/// Terminals narrower than this get a condensed table.
+const NARROW_WIDTH: u16 = 120;
+
+/// Width of the inline progress gauge in the collections table.
+const GAUGE_WIDTH: u16 = 12;
+
+/// Width of the pending-history sparkline in the collections table.
+const SPARKLINE_WIDTH: u16 = 12;
+
+/// Width of the ETA column when showing durations.
+const ETA_WIDTH: u16 = 12;
+
+/// Renders `progress` as an inline gauge with the percentage printed inside,
+/// or as plain text on narrow terminals.
+fn progress_cell(progress: Option<f64>, narrow: bool, theme: &Theme) -> Cell<'static> {
+    Cell::from(progress_line(progress, narrow, theme))
+}
+
+fn progress_line(progress: Option<f64>, narrow: bool, theme: &Theme) -> Line<'static> {
+    let label = format_progress(progress);
+    let Some(ratio) = progress else {
+        return Line::styled(label, theme.muted);
+    };
+    if narrow {
+        return Line::from(label);
+    }
+    gauge_line(&label, ratio, GAUGE_WIDTH as usize, theme)
+}
+
+/// A gauge `width` characters wide, filled up to `ratio`, with `label`
+/// centered on it.
+fn gauge_line(label: &str, ratio: f64, width: usize, theme: &Theme) -> Line<'static> {
+    let text = format!("{:^width$}", label, width = width);
+    let filled = ((ratio * width as f64).round() as usize).min(width);
+    let filled = text
+        .char_indices()
+        .nth(filled)
+        .map_or(text.len(), |(index, _)| index);
+    let (done, todo) = text.split_at(filled);
+    Line::from(vec![
+        Span::styled(done.to_string(), theme.gauge_done),
+        Span::styled(todo.to_string(), theme.gauge_todo),
+    ])
+}
+
+/// How much `current` moved since the `previous` fetch, like "▲+4", along
+/// with whether it went up. `None` on the first fetch and when it didn't move.
+fn format_delta(current: u64, previous: Option<u64>, glyphs: &Glyphs) -> Option<(String, bool)> {
+    let previous = previous?;
+    let delta = current.abs_diff(previous).to_formatted_string(&Locale::en);
+    match current.cmp(&previous) {
+        Ordering::Greater => Some((format!("{}+{}", glyphs.increase, delta), true)),
+        Ordering::Less => Some((
+            format!("{}{}{}", glyphs.decrease, glyphs.minus, delta),
+            false,
+        )),
+        Ordering::Equal => None,
+    }
+}
+
+/// [`format_delta`] after a space, colored by direction.
+fn delta_span(app: &App, current: u64, previous: Option<u64>) -> Option<Span<'static>> {
+    let (delta, increase) = format_delta(current, previous, app.glyphs)?;
+    let style = match increase {
+        true => app.theme.increase,
+        false => app.theme.decrease,
+    };
+    Some(Span::styled(format!(" {}", delta), style))
+}
+
+/// A count with thousands separators, right-aligned so that magnitudes line
+/// up down a column.
+fn number_line(n: u64) -> Line<'static> {
+    Line::from(n.to_formatted_string(&Locale::en)).alignment(Alignment::Right)
+}
+
+/// A cell showing `first` for the result itself, followed by one line per
+/// stage of an expanded row.
+fn stage_cell<'a>(
+    first: impl Into<Line<'a>>,
+    stages: &[&Stage],
+    line: impl Fn(&Stage) -> Line<'a>,
+) -> Cell<'a> {
+    let mut lines = vec![first.into()];
+    lines.extend(stages.iter().map(|stage| line(stage)));
+    Cell::from(Text::from(lines))
+}
+
+/// Explains why the collections table has no rows: nothing fetched yet, the
+/// fetch failed, the filter matches nothing, or the instance is idle.
+fn render_empty_state(app: &App, f: &mut Frame, area: Rect) {
+    let text = if !app.status.results.is_empty() {
+        vec![Line::from("No collections match the filter")]
+    } else if let Some(last_success) = app.last_success {
+        let instance = match &app.metadata.app.title {
+            Some(title) => title.to_string(),
+            None => app.current_profile().name.clone(),
+        };
+        let checked = elapsed_since(last_success, Local::now()).human(Truncate::Second);
+        vec![
+            Line::from(format!(
+                "No active jobs on {} {} last checked {} ago",
+                instance, app.glyphs.dash, checked
+            )),
+            Line::styled("Press `p` to switch to another profile", app.theme.muted),
+        ]
+    } else if !app.error_message.is_empty() {
+        vec![Line::from("Unable to fetch data, see the error below")]
+    } else {
+        vec![Line::from(format!("Fetching data{}", app.glyphs.ellipsis))]
+    };
+    let [_, area, _] = Layout::vertical([
+        Constraint::Fill(1),
+        Constraint::Length(text.len() as u16),
+        Constraint::Fill(1),
+    ])
+    .areas(area);
+    f.render_widget(Paragraph::new(text).alignment(Alignment::Center), area);
+}
+
+/// Height taken up by the collections table header and its bottom margin.
+const TABLE_HEADER_HEIGHT: u16 = 2;
+
+/// Rows of the collections table that may be on screen once the table
+/// scrolls to the selection: those from the offset or selection, whichever
+/// comes first, to a screenful past the other. The rows outside aren't built.
+fn row_window(state: &TableState, total: usize, height: usize) -> Range<usize> {
+    let offset = state.offset().min(total.saturating_sub(1));
+    let (start, end) = match state.selected() {
+        Some(selected) => {
+            let selected = selected.min(total.saturating_sub(1));
+            (
+                offset.min(selected.saturating_sub(height)),
+                offset.max(selected),
+            )
+        }
+        None => (offset, offset),
+    };
+    start..(end + height + 1).min(total)
+}
+
+/// Renders `table`, holding only the rows from `first` on, as if it held
+/// them all, keeping `state` in terms of all rows.
+fn render_row_window(
+    f: &mut Frame,
+    table: Table,
+    area: Rect,
+    state: &mut TableState,
+    first: usize,
+) {
+    let mut window = TableState::default()
+        .with_offset(state.offset().saturating_sub(first))
+        .with_selected(
+            state
+                .selected()
+                .map(|selected| selected.saturating_sub(first)),
+        );
+    f.render_stateful_widget(table, area, &mut window);
+    *state.offset_mut() = window.offset() + first;
+    state.select(window.selected().map(|selected| selected + first));
+}
+
+/// Renders the collections table, adding a scrollbar and a "… N more" line
+/// when the table's `lines` don't fit into `area`. Returns the area the table
+/// itself was drawn in, excluding the scrollbar. `table` holds only the rows
+/// from `first` on.
+fn render_collections(
+    app: &mut App,
+    f: &mut Frame,
+    table: Table,
+    first: usize,
+    lines: usize,
+    area: Rect,
+) -> Rect {
+    let total = app.rows.len();
+    let capacity = area.height.saturating_sub(TABLE_HEADER_HEIGHT) as usize;
+    if lines <= capacity || capacity < 2 {
+        app.visible_rows = capacity;
+        render_row_window(f, table, area, &mut app.collection_tablestate, first);
+        let [_, body] =
+            Layout::vertical([Constraint::Length(TABLE_HEADER_HEIGHT), Constraint::Min(0)])
+                .areas(area);
+        app.collection_area = body;
+        if total == 0 {
+            render_empty_state(app, f, body);
+        }
+        return area;
+    }
+
+    let [table_area, more_area] =
+        Layout::vertical([Constraint::Min(1), Constraint::Length(1)]).areas(area);
+    let [table_area, scrollbar_area] =
+        Layout::horizontal([Constraint::Min(1), Constraint::Length(1)]).areas(table_area);
+    let visible = capacity - 1;
+    app.visible_rows = visible;
+    render_row_window(f, table, table_area, &mut app.collection_tablestate, first);
+    let [_, body] = Layout::vertical([Constraint::Length(TABLE_HEADER_HEIGHT), Constraint::Min(0)])
+        .areas(table_area);
+    app.collection_area = body;
+
+    let offset = app.collection_tablestate.offset().min(total);
+    let below = total.saturating_sub(offset + visible);
+    let ellipsis = app.glyphs.ellipsis;
+    let more = match (offset, below) {
+        (0, below) => format!("{} {} more", ellipsis, below),
+        (above, 0) => format!("{} {} more above", ellipsis, above),
+        (above, below) => format!("{} {} more above, {} more below", ellipsis, above, below),
     };
-    let last_fetch = Local::now() - app.last_fetch;
-    let last_fetch = last_fetch.human(Truncate::Second);
-    let last_fetch_text = format!(
-        "{} fetching every {}s - last fetch {} ago",
-        fetching_icon, app.config.fetch_interval, last_fetch,
-    );
     f.render_widget(
-        Block::default()
-            .title(last_fetch_text)
-            .title_alignment(Alignment::Left),
-        status_bar_chunks[1],
+        Paragraph::new(more)
+            .style(app.theme.muted)
+            .alignment(Alignment::Right),
+        more_area,
     );
+
+    let mut scrollbar_state = ScrollbarState::new(total)
+        .viewport_content_length(visible)
+        .position(offset);
+    let [_, scrollbar_area] =
+        Layout::vertical([Constraint::Length(TABLE_HEADER_HEIGHT), Constraint::Min(0)])
+            .areas(scrollbar_area);
+    f.render_stateful_widget(app.glyphs.scrollbar(), scrollbar_area, &mut scrollbar_state);
+    table_area
+}
+
+/// Renders the sums over all displayed rows, aligned with the columns of the
+/// collections table drawn in `table_area`.
+fn render_totals(
+    app: &App,
+    f: &mut Frame,
+    widths: &[Constraint],
+    narrow: bool,
+    table_area: Rect,
+    area: Rect,
+) {
+    let totals = Totals::of(app.rows.iter().map(|i| &app.status.results[*i]));
+    let mut label = vec![Span::raw(format!("Total ({})", app.rows.len()))];
+    if let Some(delta) = delta_span(app, totals.pending, app.previous_pending()) {
+        label.extend([Span::raw(", pending"), delta]);
+    }
+    // Blank marker, lock and ID columns
+    let blanks = match narrow {
+        true => 2,
+        false => 3,
+    };
+    let row = Row::new(vec![Cell::default(); blanks].into_iter().chain([
+        Cell::from(Line::from(label)),
+        Cell::from(number_line(totals.finished)),
+        Cell::from(number_line(totals.running)),
+        Cell::from(number_line(totals.pending)),
+        Cell::from(number_line(totals.failed)),
+        progress_cell(totals.progress(), narrow, &app.theme),
+    ]));
+    // Keep the columns lined up with the table, which only reserves room for
+    // the highlight symbol while a row is selected.
+    let spacing = match app.collection_tablestate.selected() {
+        Some(_) => HighlightSpacing::Always,
+        None => HighlightSpacing::Never,
+    };
+    let table = Table::new([row], widths.to_vec())
+        .style(app.theme.totals)
+        .highlight_symbol(HIGHLIGHT_SYMBOL)
+        .highlight_spacing(spacing);
     f.render_widget(
-        Block::default()
-            .title("Shortcuts: `q`, `^C`, `Esc` - quit, `p` - select profile")
-            .title_alignment(Alignment::Right),
-        status_bar_chunks[2],
+        table,
+        Rect::new(table_area.x, area.y, table_area.width, area.height),
     );
+}
 
-    if app.show_profile_selector() {
-        let popup_block = Block::default()
-            .title("Select profile")
-            .borders(Borders::ALL);
-
-        let area = centered_rect(40, 25, f.area());
-        f.render_widget(popup_block.clone(), area);
-
-        let mut rows = Vec::new();
-        for (idx, profile) in app.config.profiles.clone().into_iter().enumerate() {
-            rows.push(Row::new([profile.name.to_string()]));
-            if app.current_profile == profile.index {
-                app.profile_tablestate.select(Some(idx))
-            }
+/// Renders the jobs tab: the collections table and details of the selected one.
+fn render_jobs(app: &mut App, f: &mut Frame, area: Rect) {
+    if app.details_zoomed && app.has_details() {
+        // The table is hidden, so mouse clicks and paging must not use the
+        // areas it had last time.
+        app.header_columns.clear();
+        app.collection_area = Rect::default();
+        app.visible_rows = 0;
+        render_details(app, f, area, true);
+        return;
+    }
+    let show_filter = app.input_mode == InputMode::Filter || app.is_filtered();
+    // Wide terminals show the details next to the table instead of below it.
+    app.split_active = area.width >= SPLIT_WIDTH;
+    let (area, details_area) = match app.split_active {
+        true => {
+            let [table_area, details_area] = Layout::horizontal([
+                Constraint::Percentage(app.split_percent),
+                Constraint::Fill(1),
+            ])
+            .areas(area);
+            (table_area, Some(details_area))
+        }
+        false => (area, None),
+    };
+    let narrow = area.width < NARROW_WIDTH;
+    let now = Utc::now().naive_utc();
+    let timestamp_width = match app.timestamp_mode {
+        TimestampMode::Relative => 25,
+        TimestampMode::Absolute => format_timestamp(app, now, now).chars().count() as u16,
+    };
+    // The ETA column is the first to go on narrow terminals.
+    let show_eta = !narrow;
+    let eta_width = show_eta.then_some(match app.timestamp_mode {
+        TimestampMode::Relative => ETA_WIDTH,
+        TimestampMode::Absolute => timestamp_width,
+    });
+    let results: Vec<&StatusResult> = app.rows.iter().map(|i| &app.status.results[*i]).collect();
+    let glyphs = app.glyphs;
+    let widths = column_widths(&results, narrow, eta_width, timestamp_width, glyphs);
+    // Leave room for the scrollbar the table might need.
+    let label_width = label_width(&widths, area.width.saturating_sub(1)) as usize;
+    let full_label = app
+        .selected_result()
+        .map(StatusResult::label)
+        .filter(|label| label.width() > label_width);
+    let chunks = Layout::vertical([
+        Constraint::Length(show_filter as u16),
+        Constraint::Min(1),
+        Constraint::Length(!app.rows.is_empty() as u16),
+        Constraint::Length(full_label.is_some() as u16),
+        Constraint::Length(match details_area {
+            Some(_) => 0,
+            None => details_height(app, now),
+        }),
+    ])
+    .split(area);
+
+    if show_filter {
+        render_filter(app, f, chunks[0]);
+    }
+
+    app.metrics.rows_rendered = app.rows.len();
+    let query = app.filter.value().to_lowercase();
+    let theme_match = app.theme.filter_match;
+    let visual_rows = app.visual_rows();
+    let window = row_window(
+        &app.collection_tablestate,
+        app.rows.len(),
+        chunks[1].height as usize,
+    );
+    let mut rows = Vec::new();
+    for (index, result) in app
+        .rows
+        .iter()
+        .map(|i| &app.status.results[*i])
+        .enumerate()
+        .take(window.end)
+        .skip(window.start)
+    {
+        // Show timestamps that can't be parsed as they are, rather than
+        // failing the whole frame.
+        let last_update = match (&result.last_update, result.last_update_time()) {
+            (_, Some(last_update)) => Cell::from(format_timestamp(app, last_update, now))
+                .style(app.theme.last_update_age(now - last_update)),
+            (Some(t), None) => Cell::from(t.to_string()).style(app.theme.muted),
+            (None, None) => Cell::default(),
+        };
+        let stalled = result.is_stalled(now, app.config.stalled_after);
+        let mut markers = String::new();
+        if app.is_pinned(result) {
+            markers.push_str(&format!("{} ", glyphs.pin));
+        }
+        if stalled {
+            markers.push_str(&format!("{} ", glyphs.stalled));
+        }
+        let mut label = highlight_matches(
+            &result.label(),
+            &query,
+            label_width.saturating_sub(markers.width()),
+            theme_match,
+            glyphs.ellipsis,
+        );
+        if !markers.is_empty() {
+            label.spans.insert(0, Span::raw(markers));
+        }
+
+        let collection_id = match &result.collection {
+            Some(c) => c.id.to_string(),
+            None => "-".to_string(),
+        };
+        let marker = result
+            .collection
+            .as_ref()
+            .and_then(|c| app.theme.category(c.category))
+            .map(|style| Cell::from(glyphs.category).style(style))
+            .unwrap_or_default();
+        let stages = match app.expanded.contains(&result.key()) {
+            true => result.stage_list(),
+            false => Vec::new(),
+        };
+        let count = |n: u32| number_line(n as u64);
+        let key = result.key();
+        let changed = |line: Line<'static>, column| match app.theme.changed {
+            Some(style) if app.is_changed(&key, column) => line.patch_style(style),
+            _ => line,
+        };
+        let mut cells = vec![marker];
+        if !narrow {
+            let locked = result
+                .collection
+                .as_ref()
+                .is_some_and(Collection::is_locked);
+            cells.push(match locked {
+                true => Cell::from(glyphs.lock),
+                false => Cell::default(),
+            });
+        }
+        cells.extend([
+            Cell::from(collection_id),
+            stage_cell(label, &stages, |s| {
+                Line::styled(
+                    truncate(
+                        &format!("  {} {}", glyphs.stage, s.stage),
+                        label_width,
+                        glyphs.ellipsis,
+                    )
+                    .into_owned(),
+                    app.theme.muted,
+                )
+            }),
+            stage_cell(
+                changed(count(result.finished), SortColumn::Finished),
+                &stages,
+                |s| count(s.finished),
+            ),
+            stage_cell(
+                changed(count(result.running), SortColumn::Running),
+                &stages,
+                |s| count(s.running),
+            ),
+            stage_cell(
+                changed(count(result.pending), SortColumn::Pending),
+                &stages,
+                |s| count(s.pending),
+            ),
+            Cell::from(number_line(result.failures() as u64)),
+            stage_cell(
+                progress_line(result.progress(), narrow, &app.theme),
+                &stages,
+                |s| progress_line(s.progress(), true, &app.theme),
+            ),
+        ]);
+        if show_eta {
+            cells.push(Cell::from(format_eta(app, result, now)));
+        }
+        cells.extend([
+            Cell::from(
+                app.history
+                    .pending(&result.key())
+                    .map(|values| sparkline(values, SPARKLINE_WIDTH as usize, &glyphs.bars))
+                    .unwrap_or_default(),
+            ),
+            last_update,
+        ]);
+        let mut style = stripe_style(app.config.stripes, index, &app.theme).patch(row_style(
+            result,
+            stalled,
+            app.config.pending_threshold,
+            &app.theme,
+        ));
+        if visual_rows
+            .as_ref()
+            .is_some_and(|range| range.contains(&index))
+        {
+            style = style.patch(app.theme.visual);
         }
-        let profile_table = Table::new(rows, [Constraint::Min(15)])
-            .highlight_style(Style::new().add_modifier(Modifier::REVERSED))
-            .highlight_symbol(">>");
-        f.render_stateful_widget(
-            profile_table,
-            popup_block.inner(area),
-            &mut app.profile_tablestate,
+        rows.push(Row::new(cells).height(app.row_height(result)).style(style))
+    }
+    let mut header = vec![("", None)];
+    if !narrow {
+        header.push(("", None));
+    }
+    header.extend([
+        ("ID", None),
+        ("Label", Some(SortColumn::Label)),
+        ("Finished", Some(SortColumn::Finished)),
+        ("Running", Some(SortColumn::Running)),
+        ("Pending", Some(SortColumn::Pending)),
+        ("Failed", Some(SortColumn::Failed)),
+        (if narrow { "Done" } else { "Progress" }, None),
+        ("ETA", Some(SortColumn::Eta)),
+        ("Trend", None),
+        ("Last update", Some(SortColumn::LastUpdate)),
+    ]);
+    header.retain(|(_, column)| show_eta || *column != Some(SortColumn::Eta));
+    let sort_columns: Vec<Option<SortColumn>> = header.iter().map(|(_, column)| *column).collect();
+    let header = header.into_iter().map(|(name, column)| {
+        let name = match app.sort {
+            Some(sort) if column == Some(sort.column) => match sort.descending {
+                true => format!("{} {}", name, glyphs.sort_descending),
+                false => format!("{} {}", name, glyphs.sort_ascending),
+            },
+            _ => name.to_string(),
+        };
+        let numeric = matches!(
+            column,
+            Some(
+                SortColumn::Finished
+                    | SortColumn::Running
+                    | SortColumn::Pending
+                    | SortColumn::Failed
+            )
         );
+        match numeric {
+            true => Cell::from(Line::from(name).alignment(Alignment::Right)),
+            false => Cell::from(name),
+        }
+    });
+    let table_style = match app.maintenance_since {
+        Some(_) => app.theme.muted,
+        None => Style::new(),
+    };
+    let table = Table::new(rows, widths.clone())
+        .style(table_style)
+        .header(Row::new(header).style(app.theme.header).bottom_margin(1))
+        .highlight_style(app.theme.selection)
+        .highlight_symbol(HIGHLIGHT_SYMBOL);
+
+    let lines = match app.expanded.is_empty() {
+        true => app.rows.len(),
+        false => app
+            .rows
+            .iter()
+            .map(|i| app.row_height(&app.status.results[*i]) as usize)
+            .sum(),
+    };
+    let table_area = render_collections(app, f, table, window.start, lines, chunks[1]);
+    let header_area = Rect {
+        height: 1,
+        ..table_area
+    };
+    let selected = app.collection_tablestate.selected().is_some();
+    app.header_columns = column_areas(&widths, header_area, selected)
+        .iter()
+        .zip(sort_columns)
+        .filter_map(|(area, column)| Some((*area, column?)))
+        .collect();
+    if !app.rows.is_empty() {
+        render_totals(app, f, &widths, narrow, table_area, chunks[2]);
+    }
+
+    if let Some(label) = full_label {
+        f.render_widget(
+            Paragraph::new(Line::from(vec![
+                Span::styled("Label: ", app.theme.filter_label),
+                Span::raw(label),
+            ])),
+            chunks[3],
+        );
+    }
+
+    match details_area {
+        Some(details_area) => render_details(app, f, details_area, true),
+        None => render_details(app, f, chunks[4], false),
+    }
+}
+
+/// Like [`format_timestamp`], with relative timestamps reading "… ago".
+fn format_ago(app: &App, timestamp: NaiveDateTime, now: NaiveDateTime) -> String {
+    match app.timestamp_mode {
+        TimestampMode::Relative => format!("{} ago", format_timestamp(app, timestamp, now)),
+        TimestampMode::Absolute => format_timestamp(app, timestamp, now),
+    }
+}
+
+/// Short facts about a collection for the details pane, leaving out the
+/// ones the server didn't send.
+fn collection_badges(app: &App, collection: &Collection, now: NaiveDateTime) -> Vec<String> {
+    let mut badges = Vec::new();
+    if collection.secret {
+        badges.push(format!("{} secret: not public", app.glyphs.lock));
+    }
+    if collection.restricted == Some(true) {
+        badges.push(format!("{} restricted: sensitive data", app.glyphs.lock));
+    }
+    if collection.casefile {
+        badges.push("casefile".to_string());
+    }
+    badges.extend(collection.frequency_label());
+    if !collection.languages.is_empty() {
+        badges.push(collection.languages.join(", "));
+    }
+    if let Some(created) = collection.created_time() {
+        badges.push(format!("created {}", format_ago(app, created, now)));
+    }
+    if let Some(updated) = collection.data_updated_time() {
+        badges.push(format!("data updated {}", format_ago(app, updated, now)));
+    }
+    badges
+}
+
+/// Height of the details pane below the table, growing with the optional
+/// badge and timing lines.
+fn details_height(app: &App, now: NaiveDateTime) -> u16 {
+    let Some(result) = app.selected_result().filter(|_| app.has_details()) else {
+        return 9;
+    };
+    let badges = result
+        .collection
+        .as_ref()
+        .is_some_and(|collection| !collection_badges(app, collection, now).is_empty());
+    let fields = job_fields(app, result, now).is_some();
+    9 + 2 * badges as u16 + 3 * fields as u16
+}
+
+/// Key/value pairs about the timing of a job, `None` if the server reports
+/// none of its start, last activity and duration.
+fn job_fields(
+    app: &App,
+    result: &StatusResult,
+    now: NaiveDateTime,
+) -> Option<[(&'static str, String); 4]> {
+    if result.min_ts.is_none() && result.max_ts.is_none() && result.took.is_none() {
+        return None;
+    }
+    let ago = |timestamp: Option<NaiveDateTime>| match timestamp {
+        Some(timestamp) => format_ago(app, timestamp, now),
+        None => "unknown".to_string(),
+    };
+    let took = result
+        .took()
+        .map(|took| took.human(Truncate::Second).to_string())
+        .unwrap_or_else(|| "unknown".to_string());
+    let eta = match format_eta(app, result, now) {
+        eta if eta.is_empty() => "unknown".to_string(),
+        eta => eta,
+    };
+    Some([
+        ("Started", ago(result.started_time())),
+        ("Last activity", ago(result.last_activity_time())),
+        ("Took", took),
+        ("ETA", eta),
+    ])
+}
+
+/// Renders the stages of the selected result and, when the server reports
+/// them, a table of its tasks next to them, or below them if `stacked`.
+fn render_details(app: &mut App, f: &mut Frame, area: Rect, stacked: bool) {
+    let Some(result) = app.selected_result() else {
+        return;
+    };
+    let Some(stages) = &result.stages else {
+        return;
+    };
+    let stages = match stages {
+        StageOrStages::Stage(stage) => stage.to_string(),
+        StageOrStages::Stages(stages) => stages.iter().sorted_by_key(|s| &s.stage).join("\n"),
+    };
+    let now = Utc::now().naive_utc();
+    let mut body = Text::default();
+    let badges = result
+        .collection
+        .as_ref()
+        .map(|collection| collection_badges(app, collection, now))
+        .unwrap_or_default();
+    if !badges.is_empty() {
+        let badges = badges
+            .into_iter()
+            .map(|badge| Span::styled(format!(" {} ", badge), app.theme.badge));
+        body.push_line(Line::from(
+            Itertools::intersperse(badges, Span::raw(" ")).collect::<Vec<_>>(),
+        ));
+        body.push_line("");
+    }
+    if let Some(fields) = job_fields(app, result, now) {
+        let fields = fields.map(|(key, value)| format!("{}: {}", key, value));
+        let width = fields[0].width().max(fields[2].width()) + 4;
+        for row in fields.chunks(2) {
+            body.push_line(format!("{:<width$}{}", row[0], row[1], width = width));
+        }
+        body.push_line("");
+    }
+    body.extend(Text::raw(stages));
+    // Borders and padding
+    let body_height = body.height() as u16 + 4;
+    let title = match &result.collection {
+        Some(col) => format!("Collection {} <{}>", col.collection_id, col.label),
+        None => "Details".to_string(),
+    };
+    let links = [
+        ("x", "xref export", app.xref_export_url()),
+        ("X", "xref page", app.xref_page_url()),
+        ("r", "copy reconcile URL", app.reconcile_url()),
+    ];
+    let links = links
+        .iter()
+        .filter(|(_, _, url)| url.is_some())
+        .map(|(key, action, _)| format!("{} {}", key, action))
+        .join(&format!(" {} ", app.glyphs.separator));
+    let mut info_block = app
+        .glyphs
+        .rounded_block()
+        .title(title)
+        .padding(Padding::new(1, 1, 1, 1));
+    if !links.is_empty() {
+        info_block = info_block.title_bottom(Line::styled(links, app.theme.muted));
+    }
+    let info_block = Paragraph::new(body).block(info_block);
+
+    let tasks = app.selected_tasks();
+    if tasks.is_empty() {
+        f.render_widget(info_block, area);
+        return;
+    }
+    let [stages_area, tasks_area] = match stacked {
+        true => Layout::vertical([Constraint::Length(body_height), Constraint::Min(0)]).areas(area),
+        false => {
+            Layout::horizontal([Constraint::Percentage(50), Constraint::Percentage(50)]).areas(area)
+        }
+    };
+    f.render_widget(info_block, stages_area);
+
+    let rows: Vec<Row> = tasks
+        .iter()
+        .map(|(_, task)| {
+            let took = task
+                .took
+                .filter(|seconds| *seconds >= 1.0)
+                .and_then(seconds_delta)
+                .map(|took| took.human(Truncate::Second).to_string())
+                .unwrap_or_default();
+            Row::new([
+                Cell::from(task.name.clone()),
+                Cell::from(task.todo.to_formatted_string(&Locale::en)),
+                Cell::from(task.doing.to_formatted_string(&Locale::en)),
+                Cell::from(task.succeeded.to_formatted_string(&Locale::en)),
+                Cell::from(task.failed.to_formatted_string(&Locale::en)),
+                Cell::from(took),
+            ])
+        })
+        .collect();
+    let title = match app.task_sort {
+        Some(sort) => format!("Tasks (by {})", sort),
+        None => "Tasks".to_string(),
+    };
+    let border_style = match app.focus {
+        Focus::Tasks => app.theme.tab_active,
+        Focus::Collections => Style::default(),
+    };
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Min(10),
+            Constraint::Length(8),
+            Constraint::Length(8),
+            Constraint::Length(10),
+            Constraint::Length(8),
+            Constraint::Length(10),
+        ],
+    )
+    .header(
+        Row::new(["Task", "Todo", "Doing", "Succeeded", "Failed", "Took"]).style(app.theme.header),
+    )
+    .highlight_style(match app.focus {
+        Focus::Tasks => app.theme.selection,
+        Focus::Collections => Style::default(),
+    })
+    .block(
+        app.glyphs
+            .rounded_block()
+            .title(title)
+            .padding(Padding::horizontal(1))
+            .border_style(border_style),
+    );
+    f.render_stateful_widget(table, tasks_area, &mut app.task_tablestate);
+}
+
+pub fn render(app: &mut App, f: &mut Frame) {
+    let error_lines = error_lines(app, f.area().width as usize);
+    let overall = Totals::of(&app.status.results);
+    let chunks = Layout::vertical([
+        Constraint::Length(4 + overall.progress().is_some() as u16),
+        Constraint::Length(1),
+        Constraint::Length(app.maintenance_since.is_some() as u16),
+        Constraint::Min(1),
+        Constraint::Length(error_lines.len().max(1) as u16),
+        Constraint::Length(1),
+    ])
+    .split(f.area());
+    let title_block = app.glyphs.rounded_block();
+
+    let failures: u32 = app.status.results.iter().map(|r| r.failures()).sum();
+    let profile = app.current_profile();
+    let mut headline = Vec::new();
+    if let Some(title) = &app.metadata.app.title {
+        headline.push(Span::raw(format!("{} ", title)));
+    }
+    headline.extend([
+        Span::raw("("),
+        Span::styled(
+            profile.name.to_string(),
+            profile
+                .color
+                .map(|c| Style::new().fg(c))
+                .unwrap_or_default(),
+        ),
+        Span::raw(format!(
+            " {}/{}",
+            app.current_profile + 1,
+            app.config.profiles.len()
+        )),
+    ]);
+    match (&app.role, profile.is_anonymous()) {
+        (_, true) => headline.push(Span::raw(" as anonymous")),
+        (Some(role), false) => headline.push(Span::raw(format!(" as {}", role.display_name()))),
+        (None, false) => {}
+    }
+    match app.profile_health[app.current_profile] {
+        ProfileHealth::AuthFailed => headline.push(Span::styled(
+            format!(" {} unauthorized", app.glyphs.stalled),
+            app.theme.title_failures,
+        )),
+        ProfileHealth::Unreachable => headline.push(Span::styled(
+            format!(" {} unreachable", app.glyphs.unreachable),
+            app.theme.title_failures,
+        )),
+        ProfileHealth::Unknown | ProfileHealth::Ok => {}
+    }
+    headline.push(Span::raw(format!("): {}", app.status.total)));
+    headline.extend(delta_span(
+        app,
+        app.status.total as u64,
+        app.previous_total().map(u64::from),
+    ));
+    headline.push(Span::raw(" jobs running"));
+    if failures > 0 {
+        headline.push(Span::styled(
+            format!(", {} failed", failures.to_formatted_string(&Locale::en)),
+            app.theme.title_failures,
+        ));
+    }
+    let now = Utc::now().naive_utc();
+    let stalled = app
+        .status
+        .results
+        .iter()
+        .filter(|r| r.is_stalled(now, app.config.stalled_after))
+        .count();
+    if stalled > 0 {
+        headline.push(Span::styled(
+            format!(", {} stalled", stalled),
+            app.theme.title_stalled,
+        ));
+    }
+    match app.follow {
+        Follow::On => headline.extend([Span::raw(" "), Span::styled("FOLLOW", app.theme.badge)]),
+        Follow::Suspended => headline.push(Span::styled(" FOLLOW suspended", app.theme.muted)),
+        Follow::Off => {}
+    }
+    if let Some(frozen) = &app.frozen {
+        headline.push(Span::styled(
+            format!(" frozen at {}", frozen.time.format("%H:%M:%S")),
+            app.theme.title_frozen,
+        ));
+        if frozen.fetches > 0 {
+            headline.push(Span::styled(
+                format!(" ({} fetches since)", frozen.fetches),
+                app.theme.muted,
+            ));
+        }
+    }
+    let text = vec![
+        Line::from(headline),
+        Line::from(
+            match (&app.metadata.app.version, &app.metadata.app.ftm_version) {
+                (Some(aleph), Some(ftm)) => format!("version: {}, followthemoney: {}", aleph, ftm),
+                (None, Some(ftm)) => format!("followthemoney: {}", ftm),
+                (Some(aleph), None) => format!("version: {}", aleph),
+                (None, None) => String::default(),
+            },
+        ),
+    ];
+    let title_area = title_block.inner(chunks[0]);
+    let headline_width = text[0].width();
+    let title = Paragraph::new(text)
+        .style(app.theme.title)
+        .block(title_block);
+    f.render_widget(title, chunks[0]);
+    // The host goes into whatever room the headline leaves on the right.
+    let host_width = (title_area.width as usize).saturating_sub(headline_width + 2);
+    if host_width > 1 {
+        let host = profile.host_display();
+        f.render_widget(
+            Paragraph::new(Span::styled(
+                truncate_start(&host, host_width, app.glyphs.ellipsis).into_owned(),
+                app.theme.muted,
+            ))
+            .alignment(Alignment::Right),
+            Rect {
+                height: 1,
+                ..title_area
+            },
+        );
+    }
+    if let Some(ratio) = overall.progress() {
+        let label = format!(
+            "{} ({} of {})",
+            format_progress(Some(ratio)),
+            overall.finished.to_formatted_string(&Locale::en),
+            (overall.finished + overall.running + overall.pending).to_formatted_string(&Locale::en)
+        );
+        let [_, gauge_area] =
+            Layout::vertical([Constraint::Length(2), Constraint::Length(1)]).areas(title_area);
+        f.render_widget(
+            gauge_line(&label, ratio, gauge_area.width as usize, &app.theme),
+            gauge_area,
+        );
+    }
+
+    let tabs = Tabs::new(Tab::ALL.map(|tab| format!("{} {}", tab.number(), tab)))
+        .select(app.tab.number() - 1)
+        .divider(app.glyphs.tab_divider)
+        .highlight_style(app.theme.tab_active);
+    f.render_widget(tabs, chunks[1]);
+
+    if let Some(since) = app.maintenance_since {
+        let observed = elapsed_since(since, Local::now()).human(Truncate::Minute);
+        f.render_widget(
+            Paragraph::new(format!(
+                "This instance is in maintenance mode (observed for {}), data may be stale",
+                observed
+            ))
+            .alignment(Alignment::Center)
+            .style(app.theme.maintenance_banner),
+            chunks[2],
+        );
+    }
+
+    match app.tab {
+        Tab::Jobs => render_jobs(app, f, chunks[3]),
+        Tab::System => render_system(app, f, chunks[3]),
+        Tab::Errors => render_errors(app, f, chunks[3]),
+        Tab::History => render_history(app, f, chunks[3]),
+    }
+
+    f.render_widget(
+        Paragraph::new(error_lines).style(app.theme.error),
+        chunks[4],
+    );
+
+    let version_text = format!("aleph-tui version {}", app.version);
+    let last_fetch_text = fetch_status(
+        app.is_fetching,
+        app.paused,
+        app.fetch_interval(),
+        app.next_fetch_in(),
+        app.glyphs,
+    );
+    let count = app.pending_count.map(|pending| pending.count.to_string());
+    let mut shortcuts = update::shortcuts(app);
+    if let Some(count) = &count {
+        shortcuts.insert(0, (count, "rows"));
+    }
+    // The shortcuts get whatever room is left and are cut to fit.
+    let status_bar_chunks = Layout::horizontal([
+        Constraint::Length(version_text.width() as u16 + 2),
+        Constraint::Length(last_fetch_text.width() as u16 + 2),
+        Constraint::Fill(1),
+    ])
+    .split(chunks[5]);
+    f.render_widget(
+        Block::default()
+            .title(version_text)
+            .style(app.theme.status_bar),
+        status_bar_chunks[0],
+    );
+    f.render_widget(
+        Block::default()
+            .title(last_fetch_text)
+            .title_alignment(Alignment::Left)
+            .style(app.theme.status_bar),
+        status_bar_chunks[1],
+    );
+    f.render_widget(
+        Block::default()
+            .title(shortcut_line(
+                &shortcuts,
+                status_bar_chunks[2].width as usize,
+                app.glyphs,
+            ))
+            .title_alignment(Alignment::Right)
+            .style(app.theme.status_bar),
+        status_bar_chunks[2],
+    );
+
+    if app.current_view == CurrentView::ErrorPopup {
+        render_error_popup(app, f);
+    }
+
+    if app.show_profile_selector() {
+        render_profile_selector(app, f);
+    }
+
+    if app.current_view == CurrentView::CommandPalette {
+        render_command_palette(app, f);
+    }
+
+    if app.current_view == CurrentView::Inspector {
+        render_inspector(app, f);
+    }
+
+    if let Some(action) = app
+        .confirming
+        .filter(|_| app.current_view == CurrentView::Confirm)
+    {
+        render_confirm(app, f, action);
+    }
+
+    if app.show_metrics {
+        render_metrics(app, f);
+    }
+
+    render_toasts(app, f);
+}
+
+/// Renders the debug overlay with the fetch and draw metrics in the top-right
+/// corner.
+fn render_metrics(app: &App, f: &mut Frame) {
+    let metrics = &app.metrics;
+    let millis = |duration: Option<Duration>| match duration {
+        Some(duration) => format!("{:.1} ms", duration.as_secs_f64() * 1000.0),
+        None => app.glyphs.dash.to_string(),
+    };
+    let lines = [
+        ("Fetch", millis(metrics.fetch_duration)),
+        (
+            "Received",
+            match metrics.bytes_received {
+                Some(bytes) => format!("{} B", bytes.to_formatted_string(&Locale::en)),
+                None => app.glyphs.dash.to_string(),
+            },
+        ),
+        ("Parse", millis(metrics.deserialize_duration)),
+        (
+            "Request ID",
+            metrics
+                .request_id
+                .clone()
+                .unwrap_or(app.glyphs.dash.to_string()),
+        ),
+        ("Rows", metrics.rows_rendered.to_string()),
+        ("Render", millis(metrics.render_duration)),
+        ("Draw", millis(metrics.draw_duration)),
+        ("Ticks/s", metrics.ticks_per_second().to_string()),
+    ];
+    let text: Vec<Line> = lines
+        .into_iter()
+        .map(|(label, value)| {
+            Line::from(vec![
+                Span::styled(format!("{:<11}", label), app.theme.filter_label),
+                Span::raw(value),
+            ])
+        })
+        .collect();
+    let width = text.iter().map(Line::width).max().unwrap_or_default() as u16 + 4;
+    let area = f.area();
+    let width = width.min(area.width);
+    let height = (text.len() as u16 + 2).min(area.height);
+    let overlay = Rect::new(area.right() - width, area.y, width, height);
+    f.render_widget(Clear, overlay);
+    f.render_widget(
+        Paragraph::new(text).block(
+            app.glyphs
+                .block()
+                .title("Debug")
+                .border_style(app.theme.popup_border)
+                .padding(Padding::horizontal(1)),
+        ),
+        overlay,
+    );
+}
+
+/// Renders the toasts in the bottom-right corner, over the status bar, the
+/// newest at the bottom.
+fn render_toasts(app: &App, f: &mut Frame) {
+    let area = f.area();
+    for (i, toast) in app.toasts.iter().rev().enumerate() {
+        let Some(y) = area.bottom().checked_sub(i as u16 + 1) else {
+            break;
+        };
+        let text = format!(
+            " {} ",
+            truncate(
+                &toast.text,
+                area.width.saturating_sub(2) as usize,
+                app.glyphs.ellipsis
+            )
+        );
+        let width = (text.width() as u16).min(area.width);
+        let toast_area = Rect::new(area.right() - width, y, width, 1);
+        let style = match toast.severity {
+            Severity::Info => app.theme.toast,
+            Severity::Warning => app.theme.toast_warning,
+        };
+        f.render_widget(Clear, toast_area);
+        f.render_widget(Paragraph::new(text).style(style), toast_area);
+    }
+}
+
+/// Renders the system tab: details about the instance and the connection to it.
+fn render_system(app: &App, f: &mut Frame, area: Rect) {
+    let profile = app.current_profile();
+    let or_unknown = |value: &Option<String>| value.clone().unwrap_or("unknown".to_string());
+    let last_fetch = format!(
+        "{} ago",
+        elapsed_since(app.last_fetch, Local::now()).human(Truncate::Second)
+    );
+    let last_success = match app.last_success {
+        Some(time) => format!(
+            "{} ago",
+            elapsed_since(time, Local::now()).human(Truncate::Second)
+        ),
+        None => "never".to_string(),
+    };
+    let mut rows = [
+        ("Instance", or_unknown(&app.metadata.app.title)),
+        ("Profile", profile.name.to_string()),
+        ("URL", profile.origin()),
+        ("Aleph", or_unknown(&app.metadata.app.version)),
+        ("followthemoney", or_unknown(&app.metadata.app.ftm_version)),
+        ("Status", app.metadata.status.to_string()),
+        (
+            "Maintenance",
+            match app.metadata.maintenance {
+                true => "yes".to_string(),
+                false => "no".to_string(),
+            },
+        ),
+        (
+            "Jobs running",
+            app.status.total.to_formatted_string(&Locale::en),
+        ),
+        (
+            "Collections",
+            app.status.results.len().to_formatted_string(&Locale::en),
+        ),
+        (
+            "Fetch interval",
+            format!("{}s", app.fetch_interval().as_secs()),
+        ),
+        ("Last fetch", last_fetch),
+        ("Last success", last_success),
+    ]
+    .map(|(name, value)| {
+        Row::new([
+            Cell::from(name).style(app.theme.filter_label),
+            Cell::from(value),
+        ])
+    })
+    .to_vec();
+    // Legend for the category markers in the jobs tab
+    let categories = app
+        .status
+        .results
+        .iter()
+        .filter_map(|r| r.collection.as_ref().map(|c| c.category))
+        .unique()
+        .sorted_by_key(|category| category.to_string())
+        .filter_map(|category| Some((category, app.theme.category(category)?)));
+    for (i, (category, style)) in categories.enumerate() {
+        let name = match i {
+            0 => "Categories",
+            _ => "",
+        };
+        rows.push(Row::new([
+            Cell::from(name).style(app.theme.filter_label),
+            Cell::from(Line::from(vec![
+                Span::styled(app.glyphs.category, style),
+                Span::raw(format!(" {}", category)),
+            ])),
+        ]));
+    }
+    let block = app
+        .glyphs
+        .rounded_block()
+        .title("System")
+        .padding(Padding::new(1, 1, 1, 1));
+    f.render_widget(
+        Table::new(rows, [Constraint::Length(16), Constraint::Fill(1)]).block(block),
+        area,
+    );
+}
+
+/// Renders the errors tab: the recorded errors, newest first, with those
+/// from other profiles than the active one dimmed.
+fn render_errors(app: &mut App, f: &mut Frame, area: Rect) {
+    let block = app
+        .glyphs
+        .rounded_block()
+        .title(format!("Errors ({})", app.errors.len()));
+    if app.errors.is_empty() {
+        f.render_widget(
+            Paragraph::new("No errors so far")
+                .style(app.theme.muted)
+                .alignment(Alignment::Center)
+                .block(block),
+            area,
+        );
+        return;
+    }
+    if app.error_tablestate.selected().is_none() {
+        app.error_tablestate.select(Some(0));
+    }
+    let profile_name = |index: usize| {
+        app.config
+            .profiles
+            .get(index)
+            .map(|profile| profile.name.clone())
+            .unwrap_or_default()
+    };
+    let profile_width = app
+        .errors
+        .iter()
+        .map(|error| profile_name(error.profile).width())
+        .max()
+        .unwrap_or_default()
+        .max("Profile".len()) as u16;
+    let rows: Vec<Row> = app
+        .errors
+        .iter()
+        .rev()
+        .map(|error| {
+            let style = match error.profile == app.current_profile {
+                true => Style::new(),
+                false => app.theme.muted,
+            };
+            Row::new([
+                Cell::from(error.time.format("%Y-%m-%d %H:%M:%S").to_string()),
+                Cell::from(profile_name(error.profile)),
+                Cell::from(error.kind.to_string()),
+                Cell::from(error.endpoint()),
+                Cell::from(error.message.lines().next().unwrap_or_default().to_string())
+                    .style(app.theme.error.patch(style)),
+            ])
+            .style(style)
+        })
+        .collect();
+    let inner = block.inner(area);
+    app.visible_errors = inner.height.saturating_sub(TABLE_HEADER_HEIGHT) as usize;
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Length(19),
+            Constraint::Length(profile_width),
+            Constraint::Length(15),
+            Constraint::Length(20),
+            Constraint::Fill(1),
+        ],
+    )
+    .header(
+        Row::new(["Time", "Profile", "Kind", "Endpoint", "Message"])
+            .style(app.theme.header)
+            .bottom_margin(1),
+    )
+    .highlight_style(app.theme.selection)
+    .highlight_symbol(HIGHLIGHT_SYMBOL)
+    .block(block);
+    f.render_stateful_widget(table, area, &mut app.error_tablestate);
+
+    if app.errors.len() <= app.visible_errors {
+        return;
+    }
+    let mut scrollbar_state = ScrollbarState::new(app.errors.len())
+        .viewport_content_length(app.visible_errors)
+        .position(app.error_tablestate.offset());
+    // Over the right border, next to the rows
+    let [_, rows_area] =
+        Layout::vertical([Constraint::Length(TABLE_HEADER_HEIGHT), Constraint::Min(0)])
+            .areas(inner);
+    let scrollbar_area = Rect {
+        x: area.right().saturating_sub(1),
+        width: 1,
+        ..rows_area
+    };
+    f.render_stateful_widget(app.glyphs.scrollbar(), scrollbar_area, &mut scrollbar_state);
+}
+
+/// Renders the history tab: a chart of the pending and running tasks of all
+/// collections over the fetches since the session or profile started.
+fn render_history(app: &App, f: &mut Frame, area: Rect) {
+    let block = app.glyphs.rounded_block().title("History");
+    if app.history.samples().len() < 2 {
+        f.render_widget(
+            Paragraph::new("Not enough fetches yet")
+                .style(app.theme.muted)
+                .alignment(Alignment::Center)
+                .block(block),
+            area,
+        );
+        return;
+    }
+    let series = [
+        (
+            "Pending",
+            app.theme.chart_pending,
+            app.history.segments(|totals| totals.pending),
+        ),
+        (
+            "Running",
+            app.theme.chart_running,
+            app.history.segments(|totals| totals.running),
+        ),
+    ];
+    let points = || {
+        series
+            .iter()
+            .flat_map(|(_, _, segments)| segments.iter().flatten())
+    };
+    let max_x = points().map(|(x, _)| *x).fold(1.0, f64::max);
+    let max_y = points().map(|(_, y)| *y).fold(0.0, f64::max);
+    // Leave some room above the highest point.
+    let max_y = (max_y * 1.1).ceil().max(1.0);
+    let mut datasets = Vec::new();
+    for (name, style, segments) in &series {
+        for (i, segment) in segments.iter().enumerate() {
+            let dataset = Dataset::default()
+                .data(segment)
+                .graph_type(GraphType::Line)
+                .marker(app.glyphs.marker)
+                .style(*style);
+            // Gaps split a series into several datasets, which share one
+            // legend entry.
+            datasets.push(match i {
+                0 => dataset.name(*name),
+                _ => dataset,
+            });
+        }
+    }
+    let elapsed = |x: f64| {
+        Duration::from_secs(x as u64)
+            .human(Truncate::Second)
+            .to_string()
+    };
+    let count = |y: f64| (y as u64).to_formatted_string(&Locale::en);
+    let chart = Chart::new(datasets)
+        .block(block)
+        .hidden_legend_constraints((Constraint::Ratio(1, 2), Constraint::Ratio(1, 2)))
+        .x_axis(
+            Axis::default()
+                .style(app.theme.muted)
+                .bounds([0.0, max_x])
+                .labels([elapsed(0.0), elapsed(max_x / 2.0), elapsed(max_x)]),
+        )
+        .y_axis(
+            Axis::default()
+                .title("tasks")
+                .style(app.theme.muted)
+                .bounds([0.0, max_y])
+                .labels([count(0.0), count(max_y / 2.0), count(max_y)]),
+        );
+    f.render_widget(chart, area);
+    app.glyphs.replace_non_ascii(f.buffer_mut(), area);
+}
+
+/// Joins key hints into a single line of at most `width` columns, dropping the
+/// hints that don't fit and marking the cut with an ellipsis.
+fn shortcut_line(shortcuts: &[(&str, &str)], width: usize, glyphs: &Glyphs) -> String {
+    let cut = format!(" {}", glyphs.ellipsis);
+    let mut line = String::new();
+    for (i, (keys, action)) in shortcuts.iter().enumerate() {
+        let hint = match i {
+            0 => format!("{} {}", keys, action),
+            _ => format!(" {} {} {}", glyphs.separator, keys, action),
+        };
+        let rest = match i + 1 < shortcuts.len() {
+            true => cut.width(),
+            false => 0,
+        };
+        if line.width() + hint.width() + rest > width {
+            if !line.is_empty() {
+                line.push_str(&cut);
+            }
+            break;
+        }
+        line.push_str(&hint);
+    }
+    line
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs::read_to_string, time::Instant};
+
+    use chrono::{Local, TimeZone};
+    use crossterm::event::KeyCode;
+    use ratatui::{backend::TestBackend, buffer::Buffer, style::Modifier, Terminal};
+
+    use super::*;
+    use crate::{
+        app::{ErrorKind, ErrorRecord},
+        models::{Role, Status},
+        sort::Sort,
+    };
+
+    /// Compares `buffer`, including styles, against `testdata/snapshots/<name>.txt`.
+    /// Run with `UPDATE_SNAPSHOTS=1` to (re)write the stored snapshot.
+    fn assert_snapshot(name: &str, buffer: &Buffer) {
+        let path = format!("testdata/snapshots/{}.txt", name);
+        let actual = format!("{:?}\n", buffer);
+        if std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+            std::fs::write(&path, actual).unwrap();
+            return;
+        }
+        let expected = read_to_string(&path).unwrap();
+        assert!(
+            actual == expected,
+            "snapshot {} differs, got:\n{}",
+            name,
+            actual
+        );
+    }
+
+    /// The app showing [`Status::test_data`] as it is.
+    fn plain_app() -> App {
+        let mut app = App::with_test_status();
+        app.version = "test".to_string();
+        app
+    }
+
+    /// Like [`plain_app`], without timestamps that change with the clock.
+    fn test_app() -> App {
+        let mut app = plain_app();
+        let mut status = Status::test_data();
+        for result in status.results.iter_mut() {
+            result.last_update = None;
+        }
+        status.results[1].remaining_time = Some(3723.0);
+        app.set_status(status);
+        app
+    }
+
+    fn render_buffer(app: &mut App, width: u16, height: u16) -> Buffer {
+        let mut terminal = Terminal::new(TestBackend::new(width, height)).unwrap();
+        app.last_fetch = Local::now();
+        app.last_fetch_instant = Instant::now();
+        terminal.draw(|f| render(app, f)).unwrap();
+        terminal.backend().buffer().clone()
+    }
+
+    #[test]
+    fn test_number_width() {
+        for n in [0, 7, 999, 1_000, 54_321, 999_999, 1_000_000, u64::MAX] {
+            assert_eq!(
+                number_width(n),
+                n.to_formatted_string(&Locale::en).width(),
+                "{}",
+                n
+            );
+        }
+    }
+
+    #[test]
+    fn test_row_window() {
+        let state = |offset, selected| {
+            TableState::default()
+                .with_offset(offset)
+                .with_selected(selected)
+        };
+        assert_eq!(row_window(&state(0, None), 0, 10), 0..0);
+        assert_eq!(row_window(&state(0, Some(2)), 1000, 10), 0..13);
+        // Selection below or above the screen, and past the end
+        assert_eq!(row_window(&state(100, Some(500)), 1000, 10), 100..511);
+        assert_eq!(row_window(&state(500, Some(100)), 1000, 10), 90..511);
+        assert_eq!(row_window(&state(995, Some(2000)), 1000, 10), 989..1000);
+    }
+
+    #[test]
+    fn test_render_scrolled_window() {
+        let mut app = test_app();
+        let mut status = Status::test_data();
+        let template = status.results[0].clone();
+        status.results = (0..500)
+            .map(|i| {
+                let mut result = template.clone();
+                result.collection.as_mut().unwrap().id = format!("c{}", i);
+                result
+            })
+            .collect();
+        app.set_status(status);
+        app.collection_tablestate.select(Some(300));
+        let key = app.selected_result().unwrap().key();
+        let buffer = format!("{:?}", render_buffer(&mut app, 140, 30));
+        assert!(buffer.contains(&format!(" {} ", key)));
+        let offset = app.collection_tablestate.offset();
+        assert!(offset <= 300 && offset + app.visible_rows > 300);
+        assert_eq!(app.collection_tablestate.selected(), Some(300));
+
+        app.collection_tablestate.select(Some(5));
+        let key = app.selected_result().unwrap().key();
+        let buffer = format!("{:?}", render_buffer(&mut app, 140, 30));
+        assert!(buffer.contains(&format!(" {} ", key)));
+        assert_eq!(app.collection_tablestate.offset(), 5);
+    }
+
+    #[test]
+    fn test_render_degenerate_sizes() {
+        let sizes = [(0, 0), (1, 1), (2, 2), (3, 40), (200, 1), (10, 3), (40, 6)];
+        for (width, height) in sizes {
+            for tab in Tab::ALL {
+                for view in 0..7 {
+                    let mut app = plain_app();
+                    app.collection_tablestate.select(Some(1));
+                    app.split_active = view % 2 == 0;
+                    app.show_metrics = view == 1;
+                    app.select_tab(tab.number());
+                    app.toast("Resized");
+                    match view {
+                        0 => app.inspect_selected(),
+                        1 => app.open_command_palette(),
+                        2 => app.confirm(Action::Quit),
+                        3 => app.show_error_popup(),
+                        4 => app.current_view = CurrentView::ProfileSwitcher,
+                        5 => {
+                            app.input_mode = InputMode::Filter;
+                            app.details_zoomed = true;
+                        }
+                        _ => {}
+                    }
+                    render_buffer(&mut app, width, height);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_render_default_theme() {
+        let mut app = test_app();
+        app.collection_tablestate.select(Some(1));
+        app.error_message = "something went wrong".to_string();
+        let buffer = render_buffer(&mut app, 140, 24);
+        assert_snapshot("main", &buffer);
+    }
+
+    #[test]
+    fn test_render_sizes() {
+        for (width, height) in [(100, 30), (140, 40), (200, 50)] {
+            let mut app = plain_app();
+            app.timestamp_mode = TimestampMode::Absolute;
+            app.collection_tablestate.select(Some(0));
+            let buffer = render_buffer(&mut app, width, height);
+            assert_snapshot(&format!("main_{}x{}", width, height), &buffer);
+
+            app.toggle_profile_selector();
+            let buffer = render_buffer(&mut app, width, height);
+            assert_snapshot(&format!("profile_selector_{}x{}", width, height), &buffer);
+        }
+    }
+
+    #[test]
+    fn test_render_error_popup() {
+        let mut app = test_app();
+        app.error_message = "error sending request".to_string();
+        app.last_error = Some(ErrorRecord {
+            time: Local.with_ymd_and_hms(2024, 11, 2, 14, 3, 11).unwrap(),
+            url: "url1/api/2/status".to_string(),
+            message: "error sending request: ".repeat(20),
+            profile: 0,
+            kind: ErrorKind::Status(502),
+        });
+        app.show_error_popup();
+        app.error_scroll = 100;
+        let buffer = render_buffer(&mut app, 140, 24);
+        assert_snapshot("error_popup", &buffer);
+    }
+
+    #[test]
+    fn test_render_tabs() {
+        let mut app = test_app();
+        let mut other = app.config.profiles[0].clone();
+        other.index = 1;
+        other.name = "other".to_string();
+        app.config.profiles.push(other);
+        app.errors.push_back(ErrorRecord {
+            time: Local.with_ymd_and_hms(2024, 11, 2, 14, 3, 11).unwrap(),
+            url: "url1/api/2/status".to_string(),
+            message: "error sending request\ncaused by: connection refused".to_string(),
+            profile: 0,
+            kind: ErrorKind::Connect,
+        });
+        app.errors.push_back(ErrorRecord {
+            time: Local.with_ymd_and_hms(2024, 11, 2, 14, 5, 0).unwrap(),
+            url: "https://aleph.example.org/api/2/status".to_string(),
+            message: "HTTP status client error (401 Unauthorized)".to_string(),
+            profile: 1,
+            kind: ErrorKind::Status(401),
+        });
+        app.tab = Tab::System;
+        let buffer = render_buffer(&mut app, 140, 24);
+        assert_snapshot("system_tab", &buffer);
+        app.tab = Tab::Errors;
+        let buffer = render_buffer(&mut app, 140, 24);
+        assert_snapshot("errors_tab", &buffer);
+    }
+
+    #[test]
+    fn test_render_history_tab() {
+        let mut app = test_app();
+        app.tab = Tab::History;
+        app.history.clear();
+        let content = format!("{:?}", render_buffer(&mut app, 140, 24));
+        assert!(content.contains("Not enough fetches yet"));
+
+        let start = Instant::now();
+        let samples = [
+            (0, Some((900, 40))),
+            (30, Some((700, 60))),
+            (60, None),
+            (90, Some((400, 50))),
+            (120, Some((150, 30))),
+        ];
+        for (seconds, counts) in samples {
+            let totals = counts.map(|(pending, running)| Totals {
+                pending,
+                running,
+                ..Totals::default()
+            });
+            app.history
+                .record_sample(start + Duration::from_secs(seconds), totals);
+        }
+        let buffer = render_buffer(&mut app, 140, 24);
+        assert_snapshot("history_tab", &buffer);
+
+        app.glyphs = Glyphs::new(true);
+        let buffer = render_buffer(&mut app, 140, 24);
+        for cell in buffer.content() {
+            assert!(cell.symbol().is_ascii(), "{:?} is not ASCII", cell.symbol());
+        }
+    }
+
+    #[test]
+    fn test_hard_wrap() {
+        assert_eq!(
+            hard_wrap("abcdef", 3, 3),
+            (vec!["abc".into(), "def".into()], false)
+        );
+        assert_eq!(
+            hard_wrap("abcdefghij", 3, 3),
+            (vec!["abc".into(), "def".into(), "ghi".into()], true)
+        );
+        assert_eq!(hard_wrap("", 3, 3), (vec!["".into()], false));
+    }
+
+    #[test]
+    fn test_render_long_error() {
+        let mut app = test_app();
+        app.error_message =
+            "error sending request for url (https://aleph.example.org/api/2/status): \
+            operation timed out after 30 seconds of waiting for the server to respond to us"
+                .repeat(3);
+        let buffer = render_buffer(&mut app, 140, 24);
+        assert_snapshot("long_error", &buffer);
+    }
+
+    #[test]
+    fn test_fetch_status() {
+        let interval = Duration::from_secs(5);
+        let glyphs = &Glyphs::UNICODE;
+        assert_eq!(
+            fetch_status(true, false, interval, Duration::from_secs(3), glyphs),
+            "🔄 fetching…"
+        );
+        assert_eq!(
+            fetch_status(
+                true,
+                false,
+                interval,
+                Duration::from_secs(3),
+                &Glyphs::ASCII
+            ),
+            "fetching..."
+        );
+        assert_eq!(
+            fetch_status(false, false, interval, Duration::from_millis(1200), glyphs),
+            "fetching every 5s - next fetch in 2s"
+        );
+        assert_eq!(
+            fetch_status(false, false, interval, Duration::ZERO, glyphs),
+            "fetching every 5s - next fetch now"
+        );
+        assert_eq!(
+            fetch_status(false, true, interval, Duration::ZERO, glyphs),
+            "⏸ paused — press space to resume"
+        );
+        assert_eq!(
+            fetch_status(true, true, interval, Duration::ZERO, glyphs),
+            "🔄 fetching…"
+        );
+    }
+
+    #[test]
+    fn test_format_delta() {
+        let glyphs = &Glyphs::UNICODE;
+        assert_eq!(format_delta(57, None, glyphs), None);
+        assert_eq!(format_delta(57, Some(57), glyphs), None);
+        assert_eq!(
+            format_delta(57, Some(53), glyphs),
+            Some(("▲+4".to_string(), true))
+        );
+        assert_eq!(
+            format_delta(53, Some(1_057), glyphs),
+            Some(("▼−1,004".to_string(), false))
+        );
+        assert_eq!(
+            format_delta(53, Some(57), &Glyphs::ASCII),
+            Some(("v-4".to_string(), false))
+        );
+    }
+
+    #[test]
+    fn test_render_deltas() {
+        let mut app = test_app();
+        let content = format!("{:?}", render_buffer(&mut app, 140, 24));
+        assert!(content.contains("): 1 jobs running"));
+        assert!(!content.contains(", pending"));
+
+        app.last_success = Some(Local::now());
+        let mut status = app.status.clone();
+        status.total = 3;
+        status.results[1].pending -= 385;
+        app.set_status(status);
+        let content = format!("{:?}", render_buffer(&mut app, 140, 24));
+        assert!(content.contains("): 3 ▲+2 jobs running"));
+        assert!(content.contains("Total (2), pending ▼−385"));
+    }
+
+    #[test]
+    fn test_elapsed_since() {
+        let time = Local.with_ymd_and_hms(2024, 11, 2, 14, 3, 11).unwrap();
+        let later = time + TimeDelta::seconds(3);
+        assert_eq!(elapsed_since(time, later), TimeDelta::seconds(3));
+        assert_eq!(elapsed_since(later, time), TimeDelta::zero());
+    }
+
+    #[test]
+    fn test_column_widths() {
+        let lengths = |widths: Vec<Constraint>| {
+            widths
+                .into_iter()
+                .map(|c| match c {
+                    Constraint::Length(l) | Constraint::Min(l) => l,
+                    _ => unreachable!(),
+                })
+                .collect::<Vec<_>>()
+        };
+        // Empty table: headers and sort indicators only
+        assert_eq!(
+            lengths(column_widths(&[], false, None, 25, &Glyphs::UNICODE)),
+            vec![1, 2, 2, 20, 10, 9, 9, 8, 12, 12, 25]
+        );
+
+        let mut status = Status::test_data();
+        status.results[0].collection.as_mut().unwrap().label = "label ".repeat(50);
+        status.results[0].pending = 1_234_567_890;
+        status.results[1].finished = u32::MAX;
+        let results: Vec<&StatusResult> = status.results.iter().collect();
+        assert_eq!(
+            lengths(column_widths(
+                &results,
+                true,
+                Some(12),
+                25,
+                &Glyphs::UNICODE
+            )),
+            vec![1, 4, 20, 13, 9, 13, 8, 5, 12, 12, 25]
+        );
+    }
+
+    #[test]
+    fn test_shortcut_line() {
+        let shortcuts = [("q", "quit"), ("p", "profile"), ("/", "filter")];
+        assert_eq!(
+            shortcut_line(&shortcuts, 80, &Glyphs::UNICODE),
+            "q quit · p profile · / filter"
+        );
+        assert_eq!(
+            shortcut_line(&shortcuts, 21, &Glyphs::UNICODE),
+            "q quit · p profile …"
+        );
+        assert_eq!(shortcut_line(&shortcuts, 3, &Glyphs::UNICODE), "");
+        assert_eq!(
+            shortcut_line(&shortcuts, 23, &Glyphs::ASCII),
+            "q quit | p profile ..."
+        );
+    }
+
+    #[test]
+    fn test_highlight_matches() {
+        let style = Style::new().add_modifier(Modifier::BOLD);
+        let texts = |line: Line| {
+            line.spans
+                .into_iter()
+                .map(|span| (span.content.into_owned(), span.style == style))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            texts(highlight_matches("Leaks 2024", "lea", 20, style, "…")),
+            vec![("Lea".to_string(), true), ("ks 2024".to_string(), false)]
+        );
+        // The match continues beyond the truncated part
+        assert_eq!(
+            texts(highlight_matches("Panama papers", "papers", 10, style, "…")),
+            vec![
+                ("Panama ".to_string(), false),
+                ("pa".to_string(), true),
+                ("…".to_string(), false)
+            ]
+        );
+        assert_eq!(
+            texts(highlight_matches(
+                "Ärger mit İstanbul",
+                "i̇stan",
+                30,
+                style,
+                "…"
+            )),
+            vec![
+                ("Ärger mit ".to_string(), false),
+                ("İstan".to_string(), true),
+                ("bul".to_string(), false)
+            ]
+        );
+        assert_eq!(
+            texts(highlight_matches("数据数据", "据", 10, style, "…")),
+            vec![
+                ("数".to_string(), false),
+                ("据".to_string(), true),
+                ("数".to_string(), false),
+                ("据".to_string(), true)
+            ]
+        );
+        assert_eq!(
+            texts(highlight_matches("Leaks", "", 10, style, "…")),
+            vec![("Leaks".to_string(), false)]
+        );
+    }
+
+    #[test]
+    fn test_truncate() {
+        assert_eq!(truncate("short", 10, "…"), "short");
+        assert_eq!(truncate("exactly10!", 10, "…"), "exactly10!");
+        assert_eq!(truncate("a bit too long", 10, "…"), "a bit too…");
+        assert_eq!(truncate("漢字漢字", 5, "…"), "漢字…");
+        assert_eq!(truncate("漢字漢字", 4, "…"), "漢…");
+        assert_eq!(truncate("cafe\u{301} cafe\u{301}", 5, "…"), "cafe\u{301}…");
+        assert_eq!(truncate("👍🏽👍🏽👍🏽", 5, "…"), "👍🏽…");
+        assert_eq!(truncate("anything", 1, "…"), "…");
+        assert_eq!(truncate("anything", 0, "…"), "");
+        assert_eq!(truncate("a bit too long", 10, "..."), "a bit t...");
+        assert_eq!(truncate("anything", 2, "..."), "an");
+    }
+
+    #[test]
+    fn test_truncate_start() {
+        assert_eq!(
+            truncate_start("aleph.example.org", 20, "…"),
+            "aleph.example.org"
+        );
+        assert_eq!(
+            truncate_start("data.aleph.example.org", 12, "…"),
+            "…example.org"
+        );
+        assert_eq!(truncate_start("漢字漢字", 5, "…"), "…漢字");
+        assert_eq!(truncate_start("anything", 1, "…"), "…");
+        assert_eq!(truncate_start("anything", 0, "…"), "");
+        assert_eq!(
+            truncate_start("data.aleph.example.org", 12, "..."),
+            "...ample.org"
+        );
+    }
+
+    #[test]
+    fn test_truncate_never_exceeds_width() {
+        let labels = [
+            "very large dataset long label much data",
+            "中华人民共和国国家统计局数据",
+            "Ελληνικά e\u{301}\u{302} mixed ascii",
+            "🇺🇦 flags 👨‍👩‍👧‍👦 families 🏳️‍🌈",
+        ];
+        for (label, ellipsis) in labels.iter().cartesian_product(["…", "..."]) {
+            for width in 0..=label.width() + 1 {
+                assert!(
+                    truncate(label, width, ellipsis).width() <= width,
+                    "{} @ {}",
+                    label,
+                    width
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_render_wide_labels() {
+        let mut app = test_app();
+        let mut status = app.status.clone();
+        status.results[0].collection.as_mut().unwrap().label = "数据".repeat(30);
+        status.results[1].collection.as_mut().unwrap().label = "👍🏽".repeat(30);
+        app.set_status(status);
+        app.collection_tablestate.select(Some(0));
+        let buffer = render_buffer(&mut app, 140, 24);
+        assert_snapshot("wide_labels", &buffer);
+    }
+
+    #[test]
+    fn test_render_profile_selector() {
+        let raw = r#"
+        default = "two"
+
+        [profiles]
+            [profiles.one]
+            url = "https://aleph.example.org/"
+            token = "token1"
+
+            [profiles.two]
+            url = "http://localhost:8080/"
+            token = "token2"
+        "#;
+        let mut app = App::with_config(toml::from_str(raw).unwrap());
+        app.version = "test".to_string();
+        app.toggle_profile_selector();
+        let buffer = render_buffer(&mut app, 140, 24);
+        assert_snapshot("profile_selector", &buffer);
+    }
+
+    #[test]
+    fn test_render_command_palette() {
+        let mut app = test_app();
+        app.open_command_palette();
+        for c in "copy".chars() {
+            app.edit_palette(&KeyCode::Char(c).into());
+        }
+        let buffer = render_buffer(&mut app, 140, 24);
+        assert_snapshot("command_palette", &buffer);
+    }
+
+    #[test]
+    fn test_render_confirm() {
+        let mut app = test_app();
+        app.confirm(Action::Quit);
+        let buffer = render_buffer(&mut app, 140, 24);
+        assert_snapshot("confirm", &buffer);
+    }
+
+    #[test]
+    fn test_render_inspector() {
+        let mut app = plain_app();
+        // Relative ages in the table behind would change with the clock.
+        app.timestamp_mode = TimestampMode::Absolute;
+        app.collection_tablestate.select(Some(1));
+        app.inspect_selected();
+        let inspector = app.inspector.as_mut().unwrap();
+        "Large".chars().for_each(|c| inspector.search.insert(c));
+        let buffer = render_buffer(&mut app, 140, 24);
+        assert_snapshot("inspector", &buffer);
+    }
+
+    #[test]
+    fn test_render_metrics() {
+        let mut app = test_app();
+        app.metrics.fetch_duration = Some(Duration::from_millis(231));
+        app.metrics.bytes_received = Some(48213);
+        app.metrics.deserialize_duration = Some(Duration::from_micros(1450));
+        app.metrics.request_id = Some("3f2a9c".to_string());
+        app.toggle_metrics();
+        let buffer = render_buffer(&mut app, 140, 24);
+        assert_snapshot("metrics", &buffer);
+    }
+
+    #[test]
+    fn test_render_empty_states() {
+        let mut app = test_app();
+        app.set_status(Status::default());
+        let buffer = render_buffer(&mut app, 140, 24);
+        assert_snapshot("empty_fetching", &buffer);
+
+        app.error_message = "error sending request".to_string();
+        let buffer = render_buffer(&mut app, 140, 24);
+        assert_snapshot("empty_error", &buffer);
+
+        app.error_message = String::default();
+        app.last_success = Some(Local::now());
+        let buffer = render_buffer(&mut app, 140, 24);
+        assert_snapshot("empty_idle", &buffer);
+    }
+
+    #[test]
+    fn test_render_category_filter() {
+        let mut app = test_app();
+        app.cycle_category_filter();
+        let content = format!("{:?}", render_buffer(&mut app, 140, 24));
+        assert!(content.contains("(2 of 2)  Category: Personal investigation"));
+    }
+
+    #[test]
+    fn test_render_expanded_row() {
+        let mut app = test_app();
+        let test = read_to_string("testdata/results400.json").unwrap();
+        let mut status: Status = serde_json::from_str(&test).unwrap();
+        for result in status.results.iter_mut() {
+            result.last_update = None;
+        }
+        app.set_status(status);
+        app.collection_tablestate.select(Some(0));
+        app.expand_selected();
+        let buffer = render_buffer(&mut app, 140, 24);
+        assert_snapshot("expanded_row", &buffer);
+    }
+
+    #[test]
+    fn test_render_task_table() {
+        let mut app = test_app();
+        let test = read_to_string("testdata/results_tasks.json").unwrap();
+        let mut status: Status = serde_json::from_str(&test).unwrap();
+        status.results[0].last_update = None;
+        let collection = status.results[0].collection.as_mut().unwrap();
+        collection.created_at = None;
+        collection.data_updated_at = None;
+        app.set_status(status);
+        app.collection_tablestate.select(Some(0));
+        app.focus_tasks();
+        app.cycle_task_sort();
+        let buffer = render_buffer(&mut app, 140, 24);
+        assert_snapshot("task_table", &buffer);
+    }
+
+    #[test]
+    fn test_job_fields() {
+        let app = test_app();
+        let now =
+            NaiveDateTime::parse_from_str("2024-01-10T13:53:00", "%Y-%m-%dT%H:%M:%S").unwrap();
+        let mut result = app.status.results[1].clone();
+        assert!(job_fields(&app, &result, now).is_none());
+
+        result.min_ts = Some("2024-01-10T11:40:00.000000".to_string());
+        result.took = Some(7980.0);
+        let fields = job_fields(&app, &result, now).unwrap();
+        assert_eq!(
+            fields,
+            [
+                ("Started", "2h 13m ago".to_string()),
+                ("Last activity", "unknown".to_string()),
+                ("Took", "2h 13m".to_string()),
+                ("ETA", "1h 2m 3s".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_render_role() {
+        let mut app = test_app();
+        app.role = Some(Role {
+            name: Some("Chris".to_string()),
+            email: None,
+        });
+        let content = format!("{:?}", render_buffer(&mut app, 140, 24));
+        assert!(content.contains("(one 1/1 as Chris): 1 jobs running"));
+    }
+
+    #[test]
+    fn test_render_split() {
+        let mut app = test_app();
+        let test = read_to_string("testdata/results_tasks.json").unwrap();
+        let mut status: Status = serde_json::from_str(&test).unwrap();
+        status.results[0].last_update = None;
+        let collection = status.results[0].collection.as_mut().unwrap();
+        collection.created_at = None;
+        collection.data_updated_at = None;
+        app.set_status(status);
+        app.collection_tablestate.select(Some(0));
+        let buffer = render_buffer(&mut app, 240, 30);
+        assert_snapshot("split", &buffer);
+    }
+
+    #[test]
+    fn test_render_zoomed_details() {
+        let mut app = test_app();
+        let test = read_to_string("testdata/results_tasks.json").unwrap();
+        app.set_status(serde_json::from_str(&test).unwrap());
+        app.collection_tablestate.select(Some(0));
+        render_buffer(&mut app, 140, 24);
+        assert_ne!(app.collection_area, Rect::default());
+        app.toggle_details_zoom();
+        let content = format!("{:?}", render_buffer(&mut app, 140, 24));
+        assert!(content.contains("Collection 94 <[test] Chris 2024>"));
+        assert!(!content.contains("Finished"));
+        assert_eq!(app.collection_area, Rect::default());
+        assert_eq!(app.visible_rows, 0);
+
+        // Nothing to zoom into without a selection
+        app.collection_tablestate.select(None);
+        let content = format!("{:?}", render_buffer(&mut app, 140, 24));
+        assert!(content.contains("Finished"));
+    }
+
+    #[test]
+    fn test_render_toasts() {
+        let mut app = test_app();
+        app.toast("Copied the selected row");
+        app.toast_warning("Opening the link failed");
+        let buffer = render_buffer(&mut app, 140, 24);
+        let line = |y| {
+            (0..140)
+                .map(|x| buffer[(x, y)].symbol())
+                .collect::<String>()
+        };
+        assert!(line(22).ends_with(" Copied the selected row "));
+        assert!(line(23).ends_with(" Opening the link failed "));
+        assert_eq!(buffer[(139, 23)].style().bg, app.theme.toast_warning.bg);
+        assert!(line(23).contains("q quit"));
+    }
+
+    #[test]
+    fn test_collection_badges() {
+        let app = test_app();
+        let now =
+            NaiveDateTime::parse_from_str("2024-01-10T13:53:00", "%Y-%m-%dT%H:%M:%S").unwrap();
+        let mut collection = app.status.results[0].collection.clone().unwrap();
+        collection.created_at = None;
+        collection.casefile = false;
+        assert_eq!(
+            collection_badges(&app, &collection, now),
+            ["🔒 secret: not public", "data updated 13m 8s ago"]
+        );
+        collection.secret = false;
+        collection.casefile = true;
+        collection.frequency = Some("weekly".to_string());
+        collection.languages = vec!["de".to_string(), "en".to_string()];
+        collection.data_updated_at = None;
+        assert_eq!(
+            collection_badges(&app, &collection, now),
+            ["casefile", "updated weekly", "de, en"]
+        );
+    }
+
+    #[test]
+    fn test_render_order_is_stable() {
+        let mut app = test_app();
+        let mut status = app.status.clone();
+        let expected = render_buffer(&mut app, 140, 24);
+        status.results.reverse();
+        app.set_status(status);
+        assert_eq!(render_buffer(&mut app, 140, 24), expected);
+    }
+
+    #[test]
+    fn test_render_sorted_numbers() {
+        let mut app = test_app();
+        app.collection_tablestate.select(Some(0));
+        app.sort = Some(Sort::new(SortColumn::Finished));
+        app.set_status(app.status.clone());
+        let buffer = render_buffer(&mut app, 140, 24);
+        assert_snapshot("sorted_numbers", &buffer);
+    }
+
+    #[test]
+    fn test_header_click() {
+        let mut app = test_app();
+        let buffer = render_buffer(&mut app, 140, 24);
+        let line = |y: u16| -> String {
+            (0..buffer.area.width)
+                .map(|x| buffer.cell((x, y)).unwrap().symbol())
+                .collect()
+        };
+        let header_row = (0..buffer.area.height)
+            .find(|y| line(*y).contains("Pending"))
+            .unwrap();
+        let line = line(header_row);
+        let pending = line.find("Pending").unwrap() as u16;
+
+        app.header_click(pending, header_row);
+        assert_eq!(app.sort, Some(Sort::new(SortColumn::Pending)));
+        app.header_click(pending + 2, header_row);
+        assert!(!app.sort.unwrap().descending);
+        // Not a sortable column
+        app.header_click(1, header_row);
+        assert_eq!(app.sort.unwrap().column, SortColumn::Pending);
+    }
+
+    #[test]
+    fn test_render_stripes() {
+        let mut app = test_app();
+        app.collection_tablestate.select(Some(0));
+        let buffer = render_buffer(&mut app, 140, 24);
+        assert_snapshot("stripes_off", &buffer);
+
+        app.config.stripes = true;
+        let buffer = render_buffer(&mut app, 140, 24);
+        assert_snapshot("stripes_on", &buffer);
+    }
+
+    #[test]
+    fn test_render_ascii() {
+        let assert_ascii = |buffer: &Buffer| {
+            for cell in buffer.content() {
+                assert!(cell.symbol().is_ascii(), "{:?} is not ASCII", cell.symbol());
+            }
+        };
+        let mut app = test_app();
+        app.glyphs = Glyphs::new(true);
+        let status = Status::test_data();
+        app.history.record(&status);
+        app.history.record(&status);
+        app.set_status(status);
+        app.sort = Some(Sort::new(SortColumn::Finished));
+        app.is_fetching = true;
+        app.profile_health[app.current_profile] = ProfileHealth::Unreachable;
+        app.collection_tablestate.select(Some(0));
+        assert_ascii(&render_buffer(&mut app, 140, 24));
+        assert_ascii(&render_buffer(&mut app, 60, 24));
+
+        let test = read_to_string("testdata/results400.json").unwrap();
+        app.set_status(serde_json::from_str(&test).unwrap());
+        app.collection_tablestate.select(Some(0));
+        app.expand_selected();
+        assert_ascii(&render_buffer(&mut app, 140, 24));
+    }
+
+    #[test]
+    fn test_render_stalled() {
+        let mut app = plain_app();
+        let content = format!("{:?}", render_buffer(&mut app, 140, 24));
+        assert!(content.contains(", 2 stalled"));
+        assert!(content.contains("⚠ [test] Chris 2024"));
+    }
+
+    #[test]
+    fn test_render_pinned() {
+        let mut app = plain_app();
+        let stalled = app
+            .rows
+            .iter()
+            .position(|i| app.status.results[*i].label().starts_with("[test] Chris"))
+            .unwrap();
+        app.collection_tablestate.select(Some(stalled));
+        app.toggle_pin();
+        let content = format!("{:?}", render_buffer(&mut app, 140, 24));
+        assert!(content.contains("📌 ⚠ [test] Chris 2024"));
+    }
+
+    #[test]
+    fn test_render_frozen() {
+        let mut app = plain_app();
+        app.toggle_frozen();
+        app.receive_status(Status::test_data());
+        let content = format!("{:?}", render_buffer(&mut app, 140, 24));
+        assert!(content.contains(" jobs running, 2 stalled frozen at "));
+        assert!(content.contains(" (1 fetches since)"));
+        assert!(content.contains("F thaw"));
+    }
+
+    #[test]
+    fn test_render_unparseable_timestamp() {
+        let mut app = test_app();
+        let test = read_to_string("testdata/results_bad_timestamp.json").unwrap();
+        app.set_status(serde_json::from_str(&test).unwrap());
+        let buffer = render_buffer(&mut app, 140, 24);
+        let content = format!("{:?}", buffer);
+        assert!(content.contains("10/01/2024 13:40"));
+        assert!(content.contains("very large data"));
     }
 }
@@ -0,0 +1,239 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{layout::Rect, prelude::Frame, text::Span};
+use unicode_width::UnicodeWidthChar;
+
+/// A single line of text being edited, with a cursor.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Input {
+    value: String,
+    /// Position of the cursor, in characters.
+    cursor: usize,
+}
+
+impl Input {
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+
+    pub fn cursor(&self) -> usize {
+        self.cursor
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.value.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.value.clear();
+        self.cursor = 0;
+    }
+
+    /// Byte offset of the character at `position`, or the length if past the end.
+    fn byte_index(&self, position: usize) -> usize {
+        self.value
+            .char_indices()
+            .nth(position)
+            .map_or(self.value.len(), |(i, _)| i)
+    }
+
+    fn len(&self) -> usize {
+        self.value.chars().count()
+    }
+
+    pub fn insert(&mut self, c: char) {
+        self.value.insert(self.byte_index(self.cursor), c);
+        self.cursor += 1;
+    }
+
+    /// Removes the character before the cursor.
+    pub fn backspace(&mut self) {
+        if self.cursor > 0 {
+            self.cursor -= 1;
+            self.value.remove(self.byte_index(self.cursor));
+        }
+    }
+
+    /// Removes the character under the cursor.
+    pub fn delete(&mut self) {
+        if self.cursor < self.len() {
+            self.value.remove(self.byte_index(self.cursor));
+        }
+    }
+
+    pub fn left(&mut self) {
+        self.cursor = self.cursor.saturating_sub(1);
+    }
+
+    pub fn right(&mut self) {
+        self.cursor = (self.cursor + 1).min(self.len());
+    }
+
+    pub fn home(&mut self) {
+        self.cursor = 0;
+    }
+
+    pub fn end(&mut self) {
+        self.cursor = self.len();
+    }
+
+    /// Applies the editing and cursor keys, returning whether `key_event` was
+    /// one of them. Characters typed with Ctrl aren't inserted.
+    pub fn handle_key(&mut self, key_event: &KeyEvent) -> bool {
+        match key_event.code {
+            KeyCode::Char(c) if !key_event.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.insert(c)
+            }
+            KeyCode::Backspace => self.backspace(),
+            KeyCode::Delete => self.delete(),
+            KeyCode::Left => self.left(),
+            KeyCode::Right => self.right(),
+            KeyCode::Home => self.home(),
+            KeyCode::End => self.end(),
+            _ => return false,
+        }
+        true
+    }
+
+    /// Columns the text takes up.
+    pub fn width(&self) -> usize {
+        self.value
+            .chars()
+            .map(|c| c.width().unwrap_or_default())
+            .sum()
+    }
+
+    /// The part of the text shown in `width` columns and the column of the
+    /// cursor in it. The text scrolls just far enough to keep the cursor in
+    /// view, with room for the cursor after the last character.
+    pub fn visible(&self, width: usize) -> (String, usize) {
+        if width == 0 {
+            return (String::default(), 0);
+        }
+        let chars: Vec<(char, usize)> = self
+            .value
+            .chars()
+            .map(|c| (c, c.width().unwrap_or_default()))
+            .collect();
+        let cursor_column: usize = chars[..self.cursor].iter().map(|(_, w)| w).sum();
+        let mut start = 0;
+        let mut start_column = 0;
+        while cursor_column - start_column >= width && start < self.cursor {
+            start_column += chars[start].1;
+            start += 1;
+        }
+        let mut text = String::new();
+        let mut used = 0;
+        for (c, w) in &chars[start..] {
+            if used + w > width {
+                break;
+            }
+            text.push(*c);
+            used += w;
+        }
+        (text, cursor_column - start_column)
+    }
+}
+
+/// Renders `input` into the first line of `area` and puts the terminal cursor
+/// at its cursor.
+pub fn render(input: &Input, f: &mut Frame, area: Rect) {
+    let (text, cursor) = input.visible(area.width as usize);
+    f.render_widget(Span::raw(text), area);
+    f.set_cursor_position((area.x + cursor as u16, area.y));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn typed(value: &str) -> Input {
+        let mut input = Input::default();
+        value.chars().for_each(|c| input.insert(c));
+        input
+    }
+
+    #[test]
+    fn test_editing() {
+        let mut input = typed("aleph");
+        assert_eq!(input.cursor(), 5);
+        input.home();
+        input.insert('>');
+        input.end();
+        input.insert('!');
+        assert_eq!(input.value(), ">aleph!");
+
+        input.left();
+        input.left();
+        input.backspace();
+        assert_eq!(input.value(), ">aleh!");
+        input.delete();
+        assert_eq!(input.value(), ">ale!");
+        assert_eq!(input.cursor(), 4);
+
+        input.end();
+        input.delete();
+        input.right();
+        assert_eq!(input.value(), ">ale!");
+        assert_eq!(input.cursor(), 5);
+        input.home();
+        input.backspace();
+        input.left();
+        assert_eq!(input.value(), ">ale!");
+        assert_eq!(input.cursor(), 0);
+
+        input.clear();
+        assert!(input.is_empty());
+        assert_eq!(input.cursor(), 0);
+    }
+
+    #[test]
+    fn test_multi_byte() {
+        let mut input = typed("Bärbel 🔒");
+        assert_eq!(input.cursor(), 8);
+        assert_eq!(input.width(), 9);
+        input.backspace();
+        input.home();
+        input.right();
+        input.delete();
+        input.insert('ö');
+        input.insert('ß');
+        assert_eq!(input.value(), "Bößrbel ");
+        input.end();
+        input.insert('日');
+        assert_eq!(input.value(), "Bößrbel 日");
+        assert_eq!(input.cursor(), 9);
+    }
+
+    #[test]
+    fn test_handle_key() {
+        let mut input = Input::default();
+        assert!(input.handle_key(&KeyCode::Char('a').into()));
+        assert!(input.handle_key(&KeyCode::Char('b').into()));
+        assert!(input.handle_key(&KeyCode::Home.into()));
+        assert!(input.handle_key(&KeyCode::Delete.into()));
+        assert!(!input.handle_key(&KeyEvent::new(KeyCode::Char('u'), KeyModifiers::CONTROL)));
+        assert!(!input.handle_key(&KeyCode::Enter.into()));
+        assert_eq!(input.value(), "b");
+    }
+
+    #[test]
+    fn test_visible() {
+        let mut input = typed("collection");
+        assert_eq!(input.visible(0), (String::default(), 0));
+        assert_eq!(input.visible(20), ("collection".to_string(), 10));
+        assert_eq!(input.visible(11), ("collection".to_string(), 10));
+        // Scrolled to leave room for the cursor at the end
+        assert_eq!(input.visible(10), ("ollection".to_string(), 9));
+        assert_eq!(input.visible(5), ("tion".to_string(), 4));
+        input.home();
+        assert_eq!(input.visible(5), ("colle".to_string(), 0));
+        input.right();
+        input.right();
+        assert_eq!(input.visible(5), ("colle".to_string(), 2));
+
+        // Wide characters don't get cut in half
+        let input = typed("日本");
+        assert_eq!(input.visible(5), ("日本".to_string(), 4));
+        assert_eq!(input.visible(4), ("本".to_string(), 2));
+    }
+}
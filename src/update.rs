@@ -1,41 +1,1216 @@
+use std::{future::Future, time::Instant};
+
 use chrono::Local;
-use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
 
-use crate::app::{App, CurrentView};
+use crate::{
+    action::Action,
+    app::{App, CurrentView, Focus, Follow, InputFocus, InputMode, PendingKey, RowJump, Tab},
+    client::Fetched,
+    clipboard,
+    models::StatusResult,
+};
 
 pub async fn update(app: &mut App, key_event: KeyEvent) {
+    update_times(app, key_event, 1).await;
+}
+
+/// Handles `key_event` as if it was pressed `repeat` times, which only moves
+/// further than once in the collections table.
+async fn update_times(app: &mut App, key_event: KeyEvent, repeat: usize) {
+    let following = app.follow == Follow::On;
+    let selected = app.selected_result().map(StatusResult::key);
+    update_key(app, key_event, repeat).await;
+    if following {
+        app.suspend_follow_if_moved(selected);
+    }
+}
+
+async fn update_key(app: &mut App, key_event: KeyEvent, repeat: usize) {
+    app.dismiss_toasts();
+    // While typing, the input gets the keys before any shortcut.
+    match app.input_focus() {
+        Some(InputFocus::Filter) => return update_filter(app, key_event),
+        Some(InputFocus::InspectorSearch) => return update_inspector_search(app, key_event),
+        // The command palette is a popup, handled with the others below.
+        Some(InputFocus::CommandPalette) | None => {}
+    }
+    if update_sequence(app, &key_event, Instant::now()) {
+        return;
+    }
+    // Popups get their keys first, so that Esc closes them rather than
+    // quitting.
+    match app.current_view {
+        CurrentView::ErrorPopup => update_error_popup(app, key_event),
+        CurrentView::ProfileSwitcher => update_profile_selector(app, key_event).await,
+        CurrentView::CommandPalette => update_command_palette(app, key_event),
+        CurrentView::Confirm => update_confirm(app, key_event),
+        CurrentView::Inspector => update_inspector(app, key_event),
+        CurrentView::Main => update_main(app, key_event, repeat),
+    }
+}
+
+/// Handles `key_event` pressed `count` times in a row. Moves in the
+/// collections table are made at once, other keys handled one by one.
+pub async fn update_repeated(app: &mut App, key_event: KeyEvent, count: usize) {
+    let moves = app.input_focus().is_none()
+        && app.current_view == CurrentView::Main
+        && app.visual.is_none()
+        && app.tab == Tab::Jobs
+        && app.focus == Focus::Collections
+        && is_counted_move(&key_event);
+    if moves {
+        update_times(app, key_event, count).await;
+        return;
+    }
+    for _ in 0..count {
+        update(app, key_event).await;
+    }
+}
+
+/// Two-key sequences, like vim's `gg`. They all start with `g`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Sequence {
+    First,
+    /// Switches to the tab numbered like this, since digits make up counts
+    /// in the collections table.
+    Tab(usize),
+}
+
+/// The sequence `first` and `second` make up in the view `app` is in.
+fn sequence(app: &App, first: KeyCode, second: KeyCode) -> Option<Sequence> {
+    match (first, second) {
+        (KeyCode::Char('g'), KeyCode::Char('g')) => Some(Sequence::First),
+        (KeyCode::Char('g'), KeyCode::Char(c @ '1'..='9'))
+            if app.current_view == CurrentView::Main =>
+        {
+            Some(Sequence::Tab(c as usize - '0' as usize))
+        }
+        _ => None,
+    }
+}
+
+/// Whether the view `app` is in has key sequences.
+fn has_sequences(app: &App) -> bool {
+    match app.current_view {
+        CurrentView::ProfileSwitcher => true,
+        CurrentView::Main => app.tab == Tab::Jobs && app.focus == Focus::Collections,
+        _ => false,
+    }
+}
+
+/// Handles `key_event` if it starts or completes a key sequence, returning
+/// whether it did. A key that doesn't complete the pending sequence is
+/// handled on its own, the pending key is dropped.
+fn update_sequence(app: &mut App, key_event: &KeyEvent, now: Instant) -> bool {
+    let pending = app.take_pending_key(now);
+    if !has_sequences(app) || !(key_event.modifiers - KeyModifiers::SHIFT).is_empty() {
+        return false;
+    }
+    let code = key_event.code;
+    if let Some(first) = pending {
+        if let Some(sequence) = sequence(app, first, code) {
+            app.pending_count = None;
+            run_sequence(app, sequence);
+            return true;
+        }
+    }
+    if code == KeyCode::Char('g') {
+        app.pending_count = None;
+        app.pending_key = Some(PendingKey { code, time: now });
+        return true;
+    }
+    false
+}
+
+fn run_sequence(app: &mut App, sequence: Sequence) {
+    match (sequence, app.current_view) {
+        (Sequence::First, CurrentView::ProfileSwitcher) => app.profile_first(),
+        (Sequence::First, _) => app.collection_first(),
+        (Sequence::Tab(number), _) => app.select_tab(number),
+    }
+}
+
+/// Handles keys outside of popups and input modes, pressed `repeat` times
+/// in a row. Esc steps back out of task focus and sub-views, and only quits
+/// when there is nothing left to leave.
+fn update_main(app: &mut App, key_event: KeyEvent, repeat: usize) {
+    if app.visual.is_some() && app.tab == Tab::Jobs {
+        update_visual(app, key_event);
+        return;
+    }
+    // Digits typed in the collections table make up a count for the next
+    // movement rather than switching tabs. Any other key drops the count.
+    if app.tab == Tab::Jobs && app.focus == Focus::Collections {
+        if let KeyCode::Char(c @ '0'..='9') = key_event.code {
+            app.push_count_digit(c as usize - '0' as usize, Instant::now());
+            return;
+        }
+    }
+    let count = app.pending_count.take().map(|pending| pending.count);
+    if count.is_some() && key_event.code == KeyCode::Esc {
+        return;
+    }
     match key_event.code {
-        KeyCode::Esc | KeyCode::Char('q') => app.quit(),
-        KeyCode::Char('c') | KeyCode::Char('C') => {
-            if key_event.modifiers == KeyModifiers::CONTROL {
-                app.quit()
-            }
+        KeyCode::Esc if app.tab == Tab::Jobs && app.focus == Focus::Tasks => {
+            app.focus_collections()
+        }
+        KeyCode::Esc | KeyCode::Backspace if app.can_go_back() => app.go_back(),
+        KeyCode::Esc | KeyCode::Char('q') => perform(app, Action::Quit),
+        KeyCode::Char('c') | KeyCode::Char('C') if key_event.modifiers == KeyModifiers::CONTROL => {
+            app.quit()
         }
-        KeyCode::Char('p') => app.toggle_profile_selector(),
-        KeyCode::Up | KeyCode::Char('k') => match app.show_profile_selector() {
-            true => app.profile_up(),
-            false => app.collection_up(),
+        KeyCode::Char(c @ '1'..='9') => app.select_tab(c as usize - '0' as usize),
+        _ => match Action::from_key(&key_event, app) {
+            Some(action) if action.is_global() => perform(app, action),
+            // Only the first press takes the count typed in front of it.
+            _ if app.tab == Tab::Jobs => {
+                update_jobs(app, key_event, count.unwrap_or(1) + repeat - 1)
+            }
+            _ if app.tab == Tab::Errors => update_errors(app, key_event),
+            Some(action) => perform(app, action),
+            None => {}
         },
-        KeyCode::Down | KeyCode::Char('j') => match app.show_profile_selector() {
-            true => app.profile_down(),
-            false => app.collection_down(),
+    };
+}
+
+/// Whether `key_event` moves by the count typed in front of it.
+fn is_counted_move(key_event: &KeyEvent) -> bool {
+    key_event.modifiers != KeyModifiers::CONTROL
+        && matches!(
+            key_event.code,
+            KeyCode::Up | KeyCode::Down | KeyCode::Char('j') | KeyCode::Char('k')
+        )
+}
+
+/// Does what `action` stands for, whether it came from its key or the
+/// command palette, asking first if it needs confirmation.
+fn perform(app: &mut App, action: Action) {
+    match action.needs_confirmation(app) {
+        true => app.confirm(action),
+        false => run(app, action),
+    }
+}
+
+fn run(app: &mut App, action: Action) {
+    match action {
+        Action::Quit => app.quit(),
+        Action::NextTab => app.next_tab(),
+        Action::PreviousTab => app.previous_tab(),
+        Action::SwitchProfile => app.toggle_profile_selector(),
+        Action::PreviousProfile => app.switch_profile_by(-1),
+        Action::NextProfile => app.switch_profile_by(1),
+        Action::SwitchBack => app.switch_back(),
+        Action::ShowError => app.show_error_popup(),
+        Action::CommandPalette => app.open_command_palette(),
+        Action::TogglePause => app.toggle_paused(),
+        Action::FetchFaster => app.fetch_faster(),
+        Action::FetchSlower => app.fetch_slower(),
+        Action::Redraw => app.redraw(),
+        Action::FocusTasks => app.focus_tasks(),
+        Action::ZoomDetails => app.toggle_details_zoom(),
+        Action::OpenCollection => match app.selected_collection_url() {
+            Some(url) => open_url(app, &url),
+            None => app.toast_warning("No collection to open"),
         },
+        Action::XrefExport => {
+            if let Some(url) = app.xref_export_url() {
+                open_url(app, &url)
+            }
+        }
+        Action::XrefPage => {
+            if let Some(url) = app.xref_page_url() {
+                open_url(app, &url)
+            }
+        }
+        Action::CopyReconcileUrl => {
+            if let Some(url) = app.reconcile_url() {
+                copy(app, &url, "Copied the reconciliation URL")
+            }
+        }
+        Action::CopyId => {
+            if let Some(id) = app.selected_id() {
+                copy(app, &id, format!("Copied {}", id))
+            }
+        }
+        Action::CopyApiUrl => {
+            if let Some(url) = app.selected_api_url() {
+                copy(app, &url, format!("Copied {}", url))
+            }
+        }
+        Action::CopyRow => {
+            if let Some(row) = app.selected_row_tsv() {
+                copy(app, &row, "Copied the selected row")
+            }
+        }
+        Action::CopyRows => {
+            let rows = app.rows_tsv();
+            copy(app, &rows, format!("Copied {} rows", app.rows.len()))
+        }
+        Action::VisualSelect => app.start_visual(),
+        Action::WidenTable => app.widen_table(),
+        Action::NarrowTable => app.narrow_table(),
+        Action::ResetSplit => app.reset_split(),
+        Action::CycleSort => app.cycle_sort(),
+        Action::ReverseSort => app.reverse_sort(),
+        Action::Filter => app.start_filter(),
+        Action::CycleCategory => app.cycle_category_filter(),
+        Action::ToggleIdle => app.toggle_hide_idle(),
+        Action::ToggleFailures => app.toggle_failures_only(),
+        Action::ClearFilters => app.clear_all_filters(),
+        Action::ResetView => app.reset_view(),
+        Action::ToggleTimestamps => app.toggle_timestamp_mode(),
+        Action::ExpandRow => app.expand_selected(),
+        Action::CollapseRow => app.collapse_selected(),
+        Action::InspectJson => app.inspect_selected(),
+        Action::NextFailure => app.jump(RowJump::Failures),
+        Action::ToggleFollow => app.toggle_follow(),
+        Action::ToggleFreeze => app.toggle_frozen(),
+        Action::TogglePin => app.toggle_pin(),
+        Action::ClearPins => app.clear_pins(),
+        Action::ShowSelectedError => app.show_selected_error(),
+        Action::ClearErrors => app.clear_errors(),
+        Action::ToggleMetrics => app.toggle_metrics(),
+        Action::FullRefresh => app.full_refresh(),
+    }
+}
+
+/// Handles keys specific to the jobs tab, moving `count` rows with the
+/// arrow keys and `j`/`k`.
+fn update_jobs(app: &mut App, key_event: KeyEvent, count: usize) {
+    if app.focus == Focus::Tasks {
+        update_tasks(app, key_event);
+        return;
+    }
+    let control = key_event.modifiers == KeyModifiers::CONTROL;
+    match key_event.code {
+        KeyCode::Char('K') => app.collection_move(-app.jump_rows()),
+        KeyCode::Char('J') => app.collection_move(app.jump_rows()),
+        KeyCode::Up if control => app.collection_move(-app.jump_rows()),
+        KeyCode::Down if control => app.collection_move(app.jump_rows()),
+        // As many single moves, so that they wrap around the same way.
+        KeyCode::Up | KeyCode::Char('k') => (0..count).for_each(|_| app.collection_up()),
+        KeyCode::Down | KeyCode::Char('j') => (0..count).for_each(|_| app.collection_down()),
+        KeyCode::PageUp => app.collection_page_up(),
+        KeyCode::PageDown => app.collection_page_down(),
+        KeyCode::Char('n') => {
+            if let Some(jump) = app.last_jump {
+                app.jump(jump)
+            }
+        }
+        KeyCode::Home => app.collection_first(),
+        KeyCode::End | KeyCode::Char('G') => app.collection_last(),
+        KeyCode::Char('u') if key_event.modifiers == KeyModifiers::CONTROL => {
+            app.collection_half_page_up()
+        }
+        KeyCode::Char('d') if key_event.modifiers == KeyModifiers::CONTROL => {
+            app.collection_half_page_down()
+        }
+        _ => {
+            if let Some(action) = Action::from_key(&key_event, app) {
+                perform(app, action)
+            }
+        }
+    }
+}
+
+/// Handles keys while selecting a range of rows: moving extends the range,
+/// `y` copies and `e` exports it.
+fn update_visual(app: &mut App, key_event: KeyEvent) {
+    match key_event.code {
+        KeyCode::Char('c') | KeyCode::Char('C') if key_event.modifiers == KeyModifiers::CONTROL => {
+            app.quit()
+        }
+        KeyCode::Esc | KeyCode::Char('v') => app.cancel_visual(),
+        KeyCode::Up | KeyCode::Char('k') => app.collection_move(-1),
+        KeyCode::Down | KeyCode::Char('j') => app.collection_move(1),
+        KeyCode::Char('K') => app.collection_move(-app.jump_rows()),
+        KeyCode::Char('J') => app.collection_move(app.jump_rows()),
+        KeyCode::PageUp => app.collection_page_up(),
+        KeyCode::PageDown => app.collection_page_down(),
+        KeyCode::Home => app.collection_first(),
+        KeyCode::End | KeyCode::Char('G') => app.collection_last(),
+        KeyCode::Char('y') => {
+            let (tsv, len) = app.visual_tsv();
+            copy(app, &tsv, format!("Copied {} rows", len));
+            app.cancel_visual();
+        }
+        KeyCode::Char('e') => {
+            export_csv(app);
+            app.cancel_visual();
+        }
+        _ => {}
+    }
+}
+
+/// Writes the rows selected with `v` to a CSV file in the working directory.
+fn export_csv(app: &mut App) {
+    let (csv, len) = app.visual_csv();
+    let path = format!(
+        "aleph-tui-{}-{}.csv",
+        app.current_profile().name,
+        Local::now().format("%Y%m%d-%H%M%S")
+    );
+    match std::fs::write(&path, csv) {
+        Ok(()) => app.toast(format!("Exported {} rows to {}", len, path)),
+        Err(e) => app.toast_warning(format!("Exporting failed: {}", e)),
+    }
+}
+
+/// Handles keys specific to the errors tab.
+fn update_errors(app: &mut App, key_event: KeyEvent) {
+    let control = key_event.modifiers == KeyModifiers::CONTROL;
+    match key_event.code {
+        KeyCode::Char('K') => app.error_move(-app.jump_rows()),
+        KeyCode::Char('J') => app.error_move(app.jump_rows()),
+        KeyCode::Up if control => app.error_move(-app.jump_rows()),
+        KeyCode::Down if control => app.error_move(app.jump_rows()),
+        KeyCode::Up | KeyCode::Char('k') => app.error_up(),
+        KeyCode::Down | KeyCode::Char('j') => app.error_down(),
+        KeyCode::PageUp => app.error_page_up(),
+        KeyCode::PageDown => app.error_page_down(),
+        KeyCode::Home => app.error_first(),
+        KeyCode::End => app.error_last(),
+        _ => {
+            if let Some(action) = Action::from_key(&key_event, app) {
+                perform(app, action)
+            }
+        }
+    }
+}
+
+/// Opens `url` in the browser, without waiting for it.
+fn open_url(app: &mut App, url: &str) {
+    if let Err(e) = open::that_detached(url) {
+        app.toast_warning(format!("Opening {} failed: {}", url, e))
+    }
+}
+
+/// Copies `text` to the clipboard, telling how it went in a toast.
+fn copy(app: &mut App, text: &str, success: impl Into<String>) {
+    match clipboard::copy(text) {
+        Ok(()) => app.toast(success),
+        Err(e) => app.toast_warning(format!("Copying failed: {}", e)),
+    }
+}
+
+/// Handles keys while the task table of the details pane has focus.
+fn update_tasks(app: &mut App, key_event: KeyEvent) {
+    let control = key_event.modifiers == KeyModifiers::CONTROL;
+    match key_event.code {
+        KeyCode::Enter => app.focus_collections(),
+        KeyCode::Char('K') => app.task_move(-app.jump_rows()),
+        KeyCode::Char('J') => app.task_move(app.jump_rows()),
+        KeyCode::Up if control => app.task_move(-app.jump_rows()),
+        KeyCode::Down if control => app.task_move(app.jump_rows()),
+        KeyCode::Up | KeyCode::Char('k') => app.task_up(),
+        KeyCode::Down | KeyCode::Char('j') => app.task_down(),
+        KeyCode::Char('o') => app.cycle_task_sort(),
+        KeyCode::Char('z') => app.toggle_details_zoom(),
+        _ => {}
+    }
+}
+
+/// Handles clicks and the mouse wheel. Clicks outside of tables are ignored.
+pub async fn mouse(app: &mut App, mouse_event: MouseEvent) {
+    let following = app.follow == Follow::On;
+    let selected = app.selected_result().map(StatusResult::key);
+    update_mouse(app, mouse_event).await;
+    if following {
+        app.suspend_follow_if_moved(selected);
+    }
+}
+
+async fn update_mouse(app: &mut App, mouse_event: MouseEvent) {
+    let (column, row) = (mouse_event.column, mouse_event.row);
+    match (app.current_view, mouse_event.kind) {
+        (CurrentView::ErrorPopup, MouseEventKind::ScrollUp) => app.error_scroll_up(),
+        (CurrentView::ErrorPopup, MouseEventKind::ScrollDown) => app.error_scroll_down(),
+        (CurrentView::Inspector, MouseEventKind::ScrollUp) => {
+            app.inspector.iter_mut().for_each(|i| i.scroll_by(-3))
+        }
+        (CurrentView::Inspector, MouseEventKind::ScrollDown) => {
+            app.inspector.iter_mut().for_each(|i| i.scroll_by(3))
+        }
+        (CurrentView::ProfileSwitcher, MouseEventKind::ScrollUp) => app.profile_up(),
+        (CurrentView::ProfileSwitcher, MouseEventKind::ScrollDown) => app.profile_down(),
+        (CurrentView::ProfileSwitcher, MouseEventKind::Down(MouseButton::Left)) => {
+            let hit = app.profile_click(column, row);
+            if hit {
+                app.commit_profile();
+            }
+        }
+        (CurrentView::Main, MouseEventKind::ScrollUp) if app.tab == Tab::Errors => app.error_up(),
+        (CurrentView::Main, MouseEventKind::ScrollDown) if app.tab == Tab::Errors => {
+            app.error_down()
+        }
+        (CurrentView::Main, _) if app.tab != Tab::Jobs => {}
+        // Scrolling stops at the ends, even with wrap-around navigation.
+        (CurrentView::Main, MouseEventKind::ScrollUp) => app.collection_move(-1),
+        (CurrentView::Main, MouseEventKind::ScrollDown) => app.collection_move(1),
+        (CurrentView::Main, MouseEventKind::Down(MouseButton::Left)) => {
+            app.header_click(column, row);
+            app.collection_click(column, row)
+        }
+        _ => {}
+    }
+}
+
+/// Handles keys while the profile switcher is open. Browsing only moves the
+/// highlight, the active profile changes when the choice is committed with Enter.
+async fn update_profile_selector(app: &mut App, key_event: KeyEvent) {
+    match key_event.code {
+        KeyCode::Char('c') | KeyCode::Char('C') if key_event.modifiers == KeyModifiers::CONTROL => {
+            app.quit()
+        }
+        KeyCode::Esc | KeyCode::Backspace | KeyCode::Char('p') | KeyCode::Char('q') => {
+            app.toggle_profile_selector()
+        }
+        KeyCode::Up | KeyCode::Char('k') => app.profile_up(),
+        KeyCode::Down | KeyCode::Char('j') => app.profile_down(),
+        KeyCode::Home => app.profile_first(),
+        KeyCode::End | KeyCode::Char('G') => app.profile_last(),
+        KeyCode::Enter => {
+            app.commit_profile();
+        }
+        KeyCode::Char(c @ '1'..='9') => {
+            app.commit_profile_number(c as usize - '0' as usize);
+        }
+        _ => {}
+    }
+}
+
+/// Handles keys while the command palette is open: typing filters the
+/// actions, Enter closes the palette and performs the selected one.
+fn update_command_palette(app: &mut App, key_event: KeyEvent) {
+    let control = key_event.modifiers == KeyModifiers::CONTROL;
+    match key_event.code {
+        KeyCode::Char('c') | KeyCode::Char('C') if control => app.quit(),
+        KeyCode::Char('p') if control => app.close_command_palette(),
+        KeyCode::Esc => app.close_command_palette(),
         KeyCode::Enter => {
-            if app.current_view == CurrentView::ProfileSwitcher {
-                app.toggle_profile_selector();
+            let action = app.selected_palette_action();
+            app.close_command_palette();
+            if let Some(action) = action {
+                perform(app, action)
+            }
+        }
+        KeyCode::Up => app.palette_up(),
+        KeyCode::Down => app.palette_down(),
+        _ => {
+            app.edit_palette(&key_event);
+        }
+    }
+}
+
+/// Handles keys while the confirmation dialog is open, performing the action
+/// only on `y`. Nothing else gets the keys until it is answered.
+fn update_confirm(app: &mut App, key_event: KeyEvent) {
+    match key_event.code {
+        KeyCode::Char('c') | KeyCode::Char('C') if key_event.modifiers == KeyModifiers::CONTROL => {
+            app.quit()
+        }
+        KeyCode::Char('y') | KeyCode::Char('Y') => {
+            if let Some(action) = app.answer_confirmation(true) {
+                run(app, action)
             }
         }
+        KeyCode::Esc
+        | KeyCode::Backspace
+        | KeyCode::Enter
+        | KeyCode::Char('n')
+        | KeyCode::Char('N') => {
+            app.answer_confirmation(false);
+        }
+        _ => {}
+    }
+}
+
+/// Handles keys while the full-error popup is open.
+fn update_error_popup(app: &mut App, key_event: KeyEvent) {
+    match key_event.code {
+        KeyCode::Char('c') | KeyCode::Char('C') if key_event.modifiers == KeyModifiers::CONTROL => {
+            app.quit()
+        }
+        KeyCode::Esc | KeyCode::Backspace | KeyCode::Char('e') | KeyCode::Char('q') => {
+            app.close_error_popup()
+        }
+        KeyCode::Up | KeyCode::Char('k') => app.error_scroll_up(),
+        KeyCode::Down | KeyCode::Char('j') => app.error_scroll_down(),
+        _ => {}
+    }
+}
+
+fn update_inspector(app: &mut App, key_event: KeyEvent) {
+    if key_event.code == KeyCode::Char('c') && key_event.modifiers == KeyModifiers::CONTROL {
+        app.quit();
+        return;
+    }
+    let Some(inspector) = app.inspector.as_mut() else {
+        return;
+    };
+    match key_event.code {
+        KeyCode::Esc | KeyCode::Backspace | KeyCode::Char('q') => app.close_inspector(),
+        KeyCode::Up | KeyCode::Char('k') => inspector.scroll_by(-1),
+        KeyCode::Down | KeyCode::Char('j') => inspector.scroll_by(1),
+        KeyCode::PageUp => inspector.page_up(),
+        KeyCode::PageDown | KeyCode::Char(' ') => inspector.page_down(),
+        KeyCode::Home | KeyCode::Char('g') => inspector.top(),
+        KeyCode::End | KeyCode::Char('G') => inspector.bottom(),
+        KeyCode::Char('/') => {
+            inspector.search.clear();
+            inspector.searching = true;
+        }
+        KeyCode::Char('n') => inspector.next_match(),
+        KeyCode::Char('N') => inspector.previous_match(),
         _ => {}
+    }
+}
+
+/// Handles keys while the search line of the inspector has focus.
+fn update_inspector_search(app: &mut App, key_event: KeyEvent) {
+    if key_event.code == KeyCode::Char('c') && key_event.modifiers == KeyModifiers::CONTROL {
+        app.quit();
+        return;
+    }
+    let Some(inspector) = app.inspector.as_mut() else {
+        return;
     };
+    match key_event.code {
+        KeyCode::Esc => {
+            inspector.search.clear();
+            inspector.searching = false;
+        }
+        KeyCode::Enter => inspector.searching = false,
+        _ => {
+            if inspector.search.handle_key(&key_event) {
+                inspector.first_match();
+            }
+        }
+    }
 }
 
-pub(crate) async fn fetch(app: &mut App) {
-    let elapsed = Local::now() - app.last_fetch;
-    if elapsed.num_seconds() > app.config.fetch_interval {
-        app.error_message = match app.fetch().await {
-            Ok(()) => String::default(),
-            Err(e) => e.to_string(),
+/// Handles keys while the filter input line has focus.
+fn update_filter(app: &mut App, key_event: KeyEvent) {
+    match key_event.code {
+        KeyCode::Char('c') | KeyCode::Char('C') if key_event.modifiers == KeyModifiers::CONTROL => {
+            app.quit()
+        }
+        KeyCode::Esc => app.clear_filter(),
+        KeyCode::Enter => app.apply_filter(),
+        KeyCode::Up => app.collection_up(),
+        KeyCode::Down => app.collection_down(),
+        _ => {
+            app.edit_filter(&key_event);
+        }
+    }
+}
+
+/// Key hints for the status bar, matching the handlers above for the view,
+/// tab and input mode `app` is in.
+pub fn shortcuts(app: &App) -> Vec<(&'static str, &'static str)> {
+    if app.input_mode == InputMode::Filter {
+        return vec![
+            ("Enter", "apply"),
+            ("Esc", "clear"),
+            (app.glyphs.up_down, "select"),
+            ("^C", "quit"),
+        ];
+    }
+    match app.current_view {
+        CurrentView::ProfileSwitcher => {
+            return vec![
+                (app.glyphs.up_down, "select"),
+                ("Enter", "switch"),
+                ("1-9", "switch to"),
+                ("Esc", "cancel"),
+            ]
+        }
+        CurrentView::ErrorPopup => return vec![(app.glyphs.up_down, "scroll"), ("Esc", "close")],
+        CurrentView::Confirm => return vec![("y", "yes"), ("n/Esc", "no")],
+        CurrentView::CommandPalette => {
+            return vec![
+                (app.glyphs.up_down, "select"),
+                ("Enter", "run"),
+                ("Esc", "cancel"),
+            ]
+        }
+        CurrentView::Inspector => {
+            return match app.inspector.as_ref().is_some_and(|i| i.searching) {
+                true => vec![("Enter", "done"), ("Esc", "clear")],
+                false => vec![
+                    (app.glyphs.up_down, "scroll"),
+                    ("/", "search"),
+                    ("n/N", "next/previous"),
+                    ("Esc", "back"),
+                ],
+            }
+        }
+        CurrentView::Main => {}
+    }
+    if app.pending_count.is_some() {
+        return vec![("j/k", "move"), ("Esc", "cancel")];
+    }
+    if app.visual.is_some() && app.tab == Tab::Jobs {
+        return vec![
+            ("j/k", "extend"),
+            ("y", "copy"),
+            ("e", "export CSV"),
+            ("Esc", "cancel"),
+        ];
+    }
+    if app.tab == Tab::Jobs && app.focus == Focus::Tasks {
+        return vec![
+            (app.glyphs.up_down, "select task"),
+            ("o", "sort tasks"),
+            ("z", "zoom"),
+            ("Esc", "back"),
+        ];
+    }
+    let mut shortcuts = match app.can_go_back() {
+        true => vec![("Esc", "back"), ("q", "quit")],
+        false => vec![("q", "quit")],
+    };
+    shortcuts.extend([("Tab", "next tab"), ("p", "profile"), ("^P", "commands")]);
+    if app.tab == Tab::Jobs {
+        if app.frozen.is_some() {
+            shortcuts.push(("F", "thaw"));
+        }
+        shortcuts.extend([
+            (app.glyphs.up_down, "select"),
+            (app.glyphs.left_right, "stages"),
+            ("s/S", "sort"),
+            ("/", "filter"),
+            ("f", "category"),
+            ("a", "active only"),
+            ("t", "timestamps"),
+        ]);
+        if !app.selected_tasks().is_empty() {
+            shortcuts.push(("Enter", "tasks"));
+        }
+        if app.selected_result().is_some() {
+            shortcuts.extend([("o", "open"), ("y/Y", "copy id/URL"), ("T", "copy row")]);
+        }
+        if app.has_details() {
+            shortcuts.push(("z", "zoom"));
+        }
+        if app.split_active && !app.details_zoomed {
+            shortcuts.push(("</>", "resize"));
+        }
+    }
+    if app.tab == Tab::Errors && !app.errors.is_empty() {
+        shortcuts.extend([
+            (app.glyphs.up_down, "select"),
+            ("Enter", "details"),
+            ("c", "clear"),
+        ]);
+    }
+    if app.last_error.is_some() {
+        shortcuts.push(("e", "error details"));
+    }
+    shortcuts
+}
+
+/// Starts a fetch when the next fetch is due or one was asked for, unless
+/// fetching is paused or a fetch is running already. The fetch is left to
+/// the caller to run, [`fetched`] takes its result.
+pub(crate) fn fetch(app: &mut App) -> Option<impl Future<Output = Fetched> + Send + 'static> {
+    let due = !app.paused && app.next_fetch_in().is_zero();
+    if app.is_fetching || !(due || app.fetch_requested) {
+        return None;
+    }
+    app.fetch_requested = false;
+    Some(app.start_fetch())
+}
+
+/// Takes in what a fetch brought back.
+pub(crate) fn fetched(app: &mut App, fetched: Fetched) {
+    let Some(result) = app.finish_fetch(fetched) else {
+        return;
+    };
+    match result {
+        Ok(()) => app.error_message = String::default(),
+        Err(e) => app.record_error(e),
+    };
+    app.last_fetch = Local::now();
+    app.last_fetch_instant = Instant::now();
+}
+
+/// Fetches right away, regardless of when the last fetch happened, and
+/// waits for it.
+pub(crate) async fn refresh(app: &mut App) {
+    let fetch = app.start_fetch();
+    fetched(app, fetch.await);
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+        time::Duration,
+    };
+
+    use crate::{
+        client::MockClient,
+        models::{Metadata, Status},
+    };
+
+    use super::*;
+
+    async fn press(app: &mut App, code: KeyCode) {
+        update(app, code.into()).await;
+    }
+
+    /// Serves the testdata status, and no metadata with `metadata` unset,
+    /// returning the count of requests made.
+    fn mock_client(app: &mut App, metadata: bool) -> Arc<AtomicUsize> {
+        let client = MockClient {
+            status: Some(Status::test_data()),
+            metadata: metadata.then(Metadata::default),
+            ..Default::default()
         };
-        app.last_fetch = Local::now();
+        let requests = Arc::clone(&client.requests);
+        app.client = Arc::new(client);
+        requests
+    }
+
+    #[tokio::test]
+    async fn test_fetch_when_due() {
+        let mut app = App::with_profiles(&["one"]);
+        let requests = mock_client(&mut app, true);
+        app.last_fetch_instant = Instant::now();
+        assert!(fetch(&mut app).is_none());
+
+        app.paused = true;
+        app.last_fetch_instant = Instant::now() - Duration::from_secs(60);
+        assert!(fetch(&mut app).is_none());
+
+        app.fetch_requested = true;
+        let running = fetch(&mut app).unwrap();
+        assert!(!app.fetch_requested);
+        // Only one fetch at a time
+        app.fetch_requested = true;
+        assert!(fetch(&mut app).is_none());
+        assert_eq!(app.fetch_due_in(), None);
+        fetched(&mut app, running.await);
+        // Status, metadata and role
+        assert_eq!(requests.load(Ordering::SeqCst), 3);
+        assert!(!app.is_fetching);
+        assert_eq!(app.fetch_due_in(), Some(Duration::ZERO));
+        assert_eq!(app.status.results.len(), 2);
+        assert_eq!(app.metrics.request_id.as_deref(), Some("mock"));
+        assert!(app.last_fetch_instant.elapsed() < Duration::from_secs(1));
+    }
+
+    #[tokio::test]
+    async fn test_fetched_after_switching_profile() {
+        let mut app = App::with_profiles(&["one", "two"]);
+        mock_client(&mut app, true);
+        app.fetch_requested = true;
+        let running = fetch(&mut app).unwrap();
+        app.switch_profile_by(1);
+        assert!(!app.is_fetching);
+        // What came back for the previous profile is dropped
+        fetched(&mut app, running.await);
+        assert!(app.status.results.is_empty());
+        assert!(app.metrics.request_id.is_none());
+        assert!(app.fetch_requested);
+    }
+
+    #[tokio::test]
+    async fn test_refresh_errors() {
+        let mut app = App::with_profiles(&["one"]);
+        // The status is kept when only the metadata fails.
+        mock_client(&mut app, false);
+        refresh(&mut app).await;
+        assert_eq!(app.error_message, "no metadata");
+        assert_eq!(app.request_url, "url1/api/2/metadata");
+        assert_eq!(app.status.results.len(), 2);
+        assert!(app.last_success.is_none());
+
+        mock_client(&mut app, true);
+        refresh(&mut app).await;
+        assert_eq!(app.error_message, "");
+        assert!(app.last_success.is_some());
+
+        // A failed status leaves a gap in the history.
+        app.client = Arc::new(MockClient::default());
+        refresh(&mut app).await;
+        assert_eq!(app.error_message, "no status");
+        assert_eq!(app.request_url, "url1/api/2/status");
+        assert!(app.history.samples().back().unwrap().totals.is_none());
+        assert_eq!(app.status.results.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_esc_closes_popups() {
+        let mut app = App::with_profiles(&["one"]);
+        press(&mut app, KeyCode::Char('p')).await;
+        assert_eq!(app.current_view, CurrentView::ProfileSwitcher);
+        press(&mut app, KeyCode::Esc).await;
+        assert_eq!(app.current_view, CurrentView::Main);
+        assert!(!app.should_quit);
+
+        update(
+            &mut app,
+            KeyEvent::new(KeyCode::Char('p'), KeyModifiers::CONTROL),
+        )
+        .await;
+        assert_eq!(app.current_view, CurrentView::CommandPalette);
+        press(&mut app, KeyCode::Esc).await;
+        assert_eq!(app.current_view, CurrentView::Main);
+        assert!(!app.should_quit);
+
+        app.tab = Tab::Errors;
+        press(&mut app, KeyCode::Char('c')).await;
+        assert_eq!(app.current_view, CurrentView::Confirm);
+        press(&mut app, KeyCode::Esc).await;
+        assert_eq!(app.current_view, CurrentView::Main);
+        assert!(!app.should_quit);
+
+        app.tab = Tab::Jobs;
+        press(&mut app, KeyCode::Char('/')).await;
+        assert_eq!(app.input_mode, InputMode::Filter);
+        press(&mut app, KeyCode::Esc).await;
+        assert_eq!(app.input_mode, InputMode::Normal);
+        assert!(!app.should_quit);
+
+        press(&mut app, KeyCode::Esc).await;
+        assert!(app.should_quit);
+    }
+
+    async fn type_text(app: &mut App, text: &str) {
+        for c in text.chars() {
+            press(app, KeyCode::Char(c)).await;
+        }
+    }
+
+    #[tokio::test]
+    async fn test_typing_skips_shortcuts() {
+        let mut app = App::with_test_status();
+
+        press(&mut app, KeyCode::Char('/')).await;
+        assert_eq!(app.input_focus(), Some(InputFocus::Filter));
+        type_text(&mut app, "qpjgg2 ").await;
+        assert_eq!(app.filter.value(), "qpjgg2 ");
+        assert!(!app.should_quit);
+        assert_eq!(app.current_view, CurrentView::Main);
+        assert_eq!(app.pending_count, None);
+        assert!(!app.paused);
+        press(&mut app, KeyCode::Esc).await;
+        assert_eq!(app.input_focus(), None);
+
+        update(
+            &mut app,
+            KeyEvent::new(KeyCode::Char('p'), KeyModifiers::CONTROL),
+        )
+        .await;
+        type_text(&mut app, "qp").await;
+        assert_eq!(app.palette_query.value(), "qp");
+        press(&mut app, KeyCode::Esc).await;
+
+        app.collection_tablestate.select(Some(0));
+        press(&mut app, KeyCode::Char('D')).await;
+        press(&mut app, KeyCode::Char('/')).await;
+        assert_eq!(app.input_focus(), Some(InputFocus::InspectorSearch));
+        type_text(&mut app, "q").await;
+        assert_eq!(app.inspector.as_ref().unwrap().search.value(), "q");
+        press(&mut app, KeyCode::Enter).await;
+        assert_eq!(app.input_focus(), None);
+        press(&mut app, KeyCode::Char('q')).await;
+        assert_eq!(app.current_view, CurrentView::Main);
+        assert!(!app.should_quit);
+    }
+
+    #[tokio::test]
+    async fn test_tab_cycling() {
+        let mut app = App::with_profiles(&["one"]);
+        let mut tabs = Vec::new();
+        for _ in 0..4 {
+            press(&mut app, KeyCode::Tab).await;
+            tabs.push(app.tab);
+        }
+        assert_eq!(tabs, [Tab::System, Tab::Errors, Tab::History, Tab::Jobs]);
+        press(&mut app, KeyCode::BackTab).await;
+        assert_eq!(app.tab, Tab::History);
+        press(&mut app, KeyCode::BackTab).await;
+        assert_eq!(app.tab, Tab::Errors);
+
+        // Popups and text input keep Tab to themselves
+        press(&mut app, KeyCode::Char('p')).await;
+        press(&mut app, KeyCode::Tab).await;
+        assert_eq!(app.tab, Tab::Errors);
+        assert_eq!(app.current_view, CurrentView::ProfileSwitcher);
+        press(&mut app, KeyCode::Esc).await;
+
+        press(&mut app, KeyCode::Char('1')).await;
+        press(&mut app, KeyCode::Char('/')).await;
+        press(&mut app, KeyCode::Tab).await;
+        press(&mut app, KeyCode::BackTab).await;
+        assert_eq!(app.tab, Tab::Jobs);
+        assert_eq!(app.input_mode, InputMode::Filter);
+        assert!(app.filter.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_count_prefix() {
+        let mut app = App::with_test_status();
+        // In the table, digits only make up the count
+        for code in [KeyCode::Char('1'), KeyCode::Char('5'), KeyCode::Char('j')] {
+            press(&mut app, code).await;
+            assert_eq!(app.tab, Tab::Jobs);
+        }
+        assert_eq!(app.collection_tablestate.selected(), Some(1));
+        assert_eq!(app.pending_count, None);
+        press(&mut app, KeyCode::Char('2')).await;
+        press(&mut app, KeyCode::Char('3')).await;
+        assert_eq!(app.tab, Tab::Jobs);
+        assert_eq!(app.pending_count.unwrap().count, 23);
+
+        // Esc only cancels the count, other keys drop it
+        press(&mut app, KeyCode::Esc).await;
+        assert_eq!(app.pending_count, None);
+        assert!(!app.should_quit);
+        press(&mut app, KeyCode::Char('1')).await;
+        press(&mut app, KeyCode::Char('5')).await;
+        press(&mut app, KeyCode::Char('a')).await;
+        assert_eq!(app.pending_count, None);
+        press(&mut app, KeyCode::Char('k')).await;
+        assert_eq!(app.collection_tablestate.selected(), Some(0));
+
+        // g and a digit switch tabs from the table, digits do elsewhere
+        press(&mut app, KeyCode::Char('g')).await;
+        press(&mut app, KeyCode::Char('3')).await;
+        assert_eq!(app.tab, Tab::Errors);
+        assert_eq!(app.pending_count, None);
+        press(&mut app, KeyCode::Char('4')).await;
+        assert_eq!(app.tab, Tab::History);
+        press(&mut app, KeyCode::Char('1')).await;
+        assert_eq!(app.tab, Tab::Jobs);
+        assert_eq!(app.pending_count, None);
+    }
+
+    #[tokio::test]
+    async fn test_update_repeated() {
+        let mut app = App::with_profiles(&["one"]);
+        let template = Status::test_data().results[0].clone();
+        app.set_status(Status {
+            results: std::iter::repeat_n(template, 25)
+                .enumerate()
+                .map(|(id, mut result)| {
+                    result.collection.as_mut().unwrap().id = id.to_string();
+                    result
+                })
+                .collect(),
+            ..Default::default()
+        });
+        app.collection_tablestate.select(Some(0));
+        update_repeated(&mut app, KeyCode::Down.into(), 7).await;
+        assert_eq!(app.collection_tablestate.selected(), Some(7));
+        assert_eq!(app.pending_count, None);
+        update_repeated(&mut app, KeyCode::Char('k').into(), 30).await;
+        assert_eq!(app.collection_tablestate.selected(), Some(0));
+
+        // A count typed in front applies to the first of the presses only
+        press(&mut app, KeyCode::Char('1')).await;
+        press(&mut app, KeyCode::Char('0')).await;
+        update_repeated(&mut app, KeyCode::Char('j').into(), 3).await;
+        assert_eq!(app.collection_tablestate.selected(), Some(12));
+        assert_eq!(app.pending_count, None);
+        // Other keys are handled as often as they were pressed
+        update_repeated(&mut app, KeyCode::Char('K').into(), 2).await;
+        assert_eq!(app.collection_tablestate.selected(), Some(0));
+
+        // Repeated and counted moves wrap around like single ones
+        app.config.wrap_navigation = true;
+        update_repeated(&mut app, KeyCode::Up.into(), 2).await;
+        assert_eq!(app.collection_tablestate.selected(), Some(23));
+        press(&mut app, KeyCode::Char('3')).await;
+        press(&mut app, KeyCode::Char('j')).await;
+        assert_eq!(app.collection_tablestate.selected(), Some(1));
+        app.config.wrap_navigation = false;
+
+        // Elsewhere the key is handled as often as it was pressed
+        press(&mut app, KeyCode::Char('p')).await;
+        update_repeated(&mut app, KeyCode::Char('j').into(), 2).await;
+        assert_eq!(app.profile_tablestate.selected(), Some(0));
+        press(&mut app, KeyCode::Esc).await;
+        press(&mut app, KeyCode::Char('/')).await;
+        update_repeated(&mut app, KeyCode::Char('j').into(), 3).await;
+        assert_eq!(app.filter.value(), "jjj");
+    }
+
+    #[tokio::test]
+    async fn test_visual_keys() {
+        let mut app = App::with_test_status();
+        app.collection_tablestate.select(Some(0));
+        press(&mut app, KeyCode::Char('v')).await;
+        assert!(app.visual.is_some());
+        press(&mut app, KeyCode::Char('j')).await;
+        assert_eq!(app.visual_rows(), Some(0..=1));
+        // Esc cancels rather than quitting
+        press(&mut app, KeyCode::Esc).await;
+        assert!(app.visual.is_none());
+        assert!(!app.should_quit);
+        assert_eq!(app.collection_tablestate.selected(), Some(1));
+    }
+
+    #[test]
+    fn test_copy_shortcuts() {
+        let mut app = App::with_profiles(&["one"]);
+        assert!(!shortcuts(&app).contains(&("T", "copy row")));
+        app.set_status(Status::test_data());
+        app.collection_tablestate.select(Some(0));
+        let shortcuts = shortcuts(&app);
+        assert!(shortcuts.contains(&("y/Y", "copy id/URL")));
+        assert!(shortcuts.contains(&("T", "copy row")));
+    }
+
+    #[tokio::test]
+    async fn test_navigation_suspends_follow() {
+        let mut app = App::with_test_status();
+        press(&mut app, KeyCode::Char('w')).await;
+        assert_eq!(app.follow, Follow::On);
+        press(&mut app, KeyCode::Char('t')).await;
+        assert_eq!(app.follow, Follow::On);
+        let key = match app.collection_tablestate.selected() {
+            Some(0) => 'j',
+            _ => 'k',
+        };
+        press(&mut app, KeyCode::Char(key)).await;
+        assert_eq!(app.follow, Follow::Suspended);
+    }
+
+    #[tokio::test]
+    async fn test_confirm_quit() {
+        let mut app = App::with_profiles(&["one"]);
+        press(&mut app, KeyCode::Char('q')).await;
+        assert!(app.should_quit);
+
+        let mut app = App::with_profiles(&["one"]);
+        app.config.confirm_quit = true;
+        press(&mut app, KeyCode::Char('q')).await;
+        assert!(!app.should_quit);
+        assert_eq!(app.current_view, CurrentView::Confirm);
+        assert_eq!(app.confirming, Some(Action::Quit));
+        press(&mut app, KeyCode::Char('q')).await;
+        press(&mut app, KeyCode::Char('n')).await;
+        assert_eq!(app.current_view, CurrentView::Main);
+        assert!(!app.can_go_back());
+
+        press(&mut app, KeyCode::Esc).await;
+        assert_eq!(app.current_view, CurrentView::Confirm);
+        perform(&mut app, Action::Quit);
+        press(&mut app, KeyCode::Char('y')).await;
+        assert!(app.should_quit);
+        assert_eq!(app.current_view, CurrentView::Main);
+        assert!(!app.can_go_back());
+
+        // Ctrl+C doesn't ask
+        let mut app = App::with_profiles(&["one"]);
+        app.config.confirm_quit = true;
+        update(
+            &mut app,
+            KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL),
+        )
+        .await;
+        assert!(app.should_quit);
+    }
+
+    #[tokio::test]
+    async fn test_p_closes_profile_switcher() {
+        let mut app = App::with_profiles(&["prod", "staging"]);
+        press(&mut app, KeyCode::Char(']')).await;
+        assert_eq!(app.current_profile().name, "staging");
+        press(&mut app, KeyCode::Char('p')).await;
+        assert_eq!(app.current_view, CurrentView::ProfileSwitcher);
+        // Closes the switcher right away, it doesn't switch back
+        press(&mut app, KeyCode::Char('p')).await;
+        assert_eq!(app.current_profile().name, "staging");
+        assert_eq!(app.current_view, CurrentView::Main);
+    }
+
+    #[tokio::test]
+    async fn test_jump_rows() {
+        let mut app = App::with_profiles(&["one"]);
+        let mut status = Status::default();
+        let template = Status::test_data().results[0].clone();
+        for id in 0..25 {
+            let mut result = template.clone();
+            result.collection.as_mut().unwrap().id = id.to_string();
+            status.results.push(result);
+        }
+        app.set_status(status);
+        app.collection_tablestate.select(Some(0));
+
+        press(&mut app, KeyCode::Char('J')).await;
+        assert_eq!(app.collection_tablestate.selected(), Some(10));
+        update(
+            &mut app,
+            KeyEvent::new(KeyCode::Down, KeyModifiers::CONTROL),
+        )
+        .await;
+        assert_eq!(app.collection_tablestate.selected(), Some(20));
+        press(&mut app, KeyCode::Char('J')).await;
+        assert_eq!(app.collection_tablestate.selected(), Some(24));
+        press(&mut app, KeyCode::Char('K')).await;
+        assert_eq!(app.collection_tablestate.selected(), Some(14));
+        update(&mut app, KeyEvent::new(KeyCode::Up, KeyModifiers::CONTROL)).await;
+        update(&mut app, KeyEvent::new(KeyCode::Up, KeyModifiers::CONTROL)).await;
+        assert_eq!(app.collection_tablestate.selected(), Some(0));
+
+        app.config.jump_rows = 3;
+        press(&mut app, KeyCode::Char('J')).await;
+        assert_eq!(app.collection_tablestate.selected(), Some(3));
+    }
+
+    #[tokio::test]
+    async fn test_key_sequences() {
+        let mut app = App::with_test_status();
+        press(&mut app, KeyCode::Char('G')).await;
+        assert_eq!(app.collection_tablestate.selected(), Some(1));
+        press(&mut app, KeyCode::Char('g')).await;
+        press(&mut app, KeyCode::Char('g')).await;
+        assert_eq!(app.collection_tablestate.selected(), Some(0));
+        assert_eq!(app.pending_key, None);
+
+        // A lone g does nothing, the next key works as usual
+        press(&mut app, KeyCode::Char('g')).await;
+        press(&mut app, KeyCode::Char('j')).await;
+        press(&mut app, KeyCode::Char('g')).await;
+        assert_eq!(app.collection_tablestate.selected(), Some(1));
+
+        // The second g comes too late and starts a new sequence
+        let now = Instant::now();
+        app.pending_key = Some(PendingKey {
+            code: KeyCode::Char('g'),
+            time: now - Duration::from_secs(2),
+        });
+        assert!(update_sequence(&mut app, &KeyCode::Char('g').into(), now));
+        assert_eq!(app.collection_tablestate.selected(), Some(1));
+        assert!(update_sequence(&mut app, &KeyCode::Char('g').into(), now));
+        assert_eq!(app.collection_tablestate.selected(), Some(0));
+
+        // Typing in the filter isn't taken for sequences
+        press(&mut app, KeyCode::Char('/')).await;
+        press(&mut app, KeyCode::Char('g')).await;
+        press(&mut app, KeyCode::Char('g')).await;
+        assert_eq!(app.filter.value(), "gg");
+        assert_eq!(app.pending_key, None);
+    }
+
+    #[tokio::test]
+    async fn test_profile_key_sequences() {
+        let mut app = App::with_profiles(&["one", "two"]);
+        press(&mut app, KeyCode::Char('p')).await;
+        press(&mut app, KeyCode::Char('G')).await;
+        assert_eq!(app.profile_tablestate.selected(), Some(1));
+        press(&mut app, KeyCode::Char('g')).await;
+        press(&mut app, KeyCode::Char('g')).await;
+        assert_eq!(app.profile_tablestate.selected(), Some(0));
+        assert_eq!(app.current_view, CurrentView::ProfileSwitcher);
     }
 }